@@ -0,0 +1,98 @@
+//! Sorts generated Camel Cards hands the way the original solver did, by field
+//! comparison, and by packed key.
+//!
+//! Run with `cargo bench --bench day7_sort`.
+
+use advent::day7::{self, Card, Hand, HandType};
+use advent::gen::{self, HandsConfig, Rng};
+use std::cmp::Ordering;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const HANDS: usize = 200_000;
+const ROUNDS: u32 = 10;
+
+/// The hand as the solver first stored it: cards in a `Vec`, ordered through
+/// `partial_cmp` card by card, and sorted with a stable `sort`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VecHand {
+    cards: Vec<Card>,
+    bid: usize,
+    hand_type: HandType,
+}
+
+// Kept as the solver first wrote it, with `cmp` going through `partial_cmp`.
+#[allow(clippy::non_canonical_partial_ord_impl)]
+impl PartialOrd for VecHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.hand_type != other.hand_type {
+            return Some(self.hand_type.cmp(&other.hand_type));
+        }
+        for i in 0..5 {
+            if self.cards[i] == other.cards[i] {
+                continue;
+            } else {
+                return Some(self.cards[i].cmp(&other.cards[i]));
+            }
+        }
+        Some(Ordering::Equal)
+    }
+}
+
+impl Ord for VecHand {
+    fn cmp(&self, other: &VecHand) -> Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
+fn time<T: Clone>(name: &str, hands: &[T], sort: impl Fn(&mut Vec<T>)) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..ROUNDS {
+        let mut hands = hands.to_vec();
+        let start = Instant::now();
+        sort(&mut hands);
+        total += start.elapsed();
+        black_box(hands);
+    }
+    let mean = total / ROUNDS;
+    println!("{:<18} {:>10.2?}", name, mean);
+    mean
+}
+
+fn main() {
+    let config = HandsConfig {
+        hands: HANDS,
+        ..HandsConfig::default()
+    };
+    let input = gen::hands(&mut Rng::new(1), &config);
+    let start = Instant::now();
+    let hands = day7::parse_input(&input);
+    println!("parsed {} hands in {:.2?}", HANDS, start.elapsed());
+    let vec_hands = hands
+        .iter()
+        .map(|hand| VecHand {
+            cards: hand.cards.to_vec(),
+            bid: hand.bid,
+            hand_type: hand.hand_type,
+        })
+        .collect::<Vec<_>>();
+    println!("sorting {} hands, mean of {} rounds", HANDS, ROUNDS);
+    let original = time("Vec<Card> sort", &vec_hands, |h| h.sort());
+    let fields = time("by fields", &hands, |h| h.sort_by(Hand::cmp_by_fields));
+    let keys = time("by key", &hands, |h| h.sort_by_key(Hand::key));
+    let cached = time("precomputed keys", &hands, |h| {
+        let mut keyed = h
+            .iter()
+            .map(|hand| (hand.key(), hand.bid))
+            .collect::<Vec<_>>();
+        keyed.sort_by_key(|&(key, _)| key);
+        black_box(keyed);
+    });
+    let speedup = |d: Duration| original.as_secs_f64() / d.as_secs_f64();
+    println!(
+        "against Vec<Card> sort: by fields {:.1}x, by key {:.1}x, precomputed keys {:.1}x",
+        speedup(fields),
+        speedup(keys),
+        speedup(cached)
+    );
+}
@@ -0,0 +1,46 @@
+//! Computes `ADVENT_BUILD_ID`, a hash of the sources and manifest, so the
+//! answer cache can tell builds apart without reading the executable.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INPUTS: [&str; 3] = ["src", "Cargo.toml", "Cargo.lock"];
+
+// Every file under `path`, in a stable order.
+fn files(path: &Path, out: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        out.push(path.to_path_buf());
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    let mut entries = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    for entry in entries {
+        files(&entry, out);
+    }
+}
+
+// FNV-1a, as `history::input_hash` uses for inputs.
+fn hash(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn main() {
+    let mut paths = Vec::new();
+    for input in INPUTS {
+        println!("cargo:rerun-if-changed={}", input);
+        files(Path::new(input), &mut paths);
+    }
+    let mut build = 0xcbf29ce484222325u64;
+    for path in paths {
+        build = hash(build, path.to_string_lossy().as_bytes());
+        build = hash(build, &fs::read(&path).unwrap_or_default());
+    }
+    println!("cargo:rustc-env=ADVENT_BUILD_ID={:016x}", build);
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+
+pub const ANSWERS_PATH: &str = "data/answers.txt";
+pub const EXAMPLE_ANSWERS_PATH: &str = "data/test_answers.txt";
+
+#[derive(Debug, Error)]
+pub enum AnswersError {
+    #[error("could not read answers file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("line {line}: expected `<day> <part> <answer>`, got {text:?}")]
+    Parse { line: usize, text: String },
+}
+
+/// Whether a computed answer matches the known one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: usize },
+    Unknown,
+}
+
+/// Known answers, read from lines of the form "day part answer".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, u8), usize>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers, AnswersError> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let parsed = match fields[..] {
+                [day, part, answer] => day
+                    .parse()
+                    .ok()
+                    .zip(part.parse().ok())
+                    .zip(answer.parse().ok()),
+                _ => None,
+            };
+            let Some(((day, part), answer)) = parsed else {
+                return Err(AnswersError::Parse {
+                    line: i + 1,
+                    text: line.to_string(),
+                });
+            };
+            answers.insert((day, part), answer);
+        }
+        Ok(Answers { answers })
+    }
+
+    /// Loads an answers file; a missing file means no answers are known.
+    pub fn load(path: &Path) -> Result<Answers, AnswersError> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<usize> {
+        self.answers.get(&(day, part)).copied()
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: Option<usize>) -> Verdict {
+        match (self.get(day, part), answer) {
+            (Some(expected), Some(answer)) if expected == answer => Verdict::Correct,
+            (Some(expected), _) => Verdict::Wrong { expected },
+            (None, _) => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse("# day part answer\n5 1 35\n\n5 2 46\n").unwrap();
+        assert_eq!(answers.get(5, 1), Some(35));
+        assert_eq!(answers.check(5, 2, Some(46)), Verdict::Correct);
+        assert_eq!(answers.check(5, 2, None), Verdict::Wrong { expected: 46 });
+        assert_eq!(answers.check(6, 1, Some(1)), Verdict::Unknown);
+    }
+
+    #[test]
+    fn test_parse_answers_reports_line() {
+        match Answers::parse("5 1 35\n5 2\n") {
+            Err(AnswersError::Parse { line, .. }) => assert_eq!(line, 2),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_example_answers_file() {
+        let answers = Answers::load(Path::new(EXAMPLE_ANSWERS_PATH)).unwrap();
+        assert_eq!(answers.get(7, 2), Some(5905));
+    }
+}
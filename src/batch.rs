@@ -0,0 +1,234 @@
+//! Solves one day for every input in a directory, e.g. one per team member, and
+//! checks each answer against the answers file kept next to its input.
+
+use crate::answers::{Answers, Verdict};
+use crate::registry::Solution;
+use crate::runner::Outcome;
+use core::fmt;
+use std::fmt::Formatter;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Extension of the answers file for an input: `alice.txt` is checked against
+/// `alice.answers`, written like `data/answers.txt`.
+pub const ANSWERS_EXTENSION: &str = "answers";
+
+/// The answers file that goes with `input`.
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_extension(ANSWERS_EXTENSION)
+}
+
+/// Every input file in `dir` in name order, skipping hidden files, answers
+/// files and subdirectories.
+pub fn inputs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut inputs = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let answers = path
+            .extension()
+            .is_some_and(|extension| extension == ANSWERS_EXTENSION);
+        if path.is_file() && !hidden && !answers {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// One part solved for one input.
+#[derive(Debug, Clone)]
+pub struct Row {
+    /// The input's file name.
+    pub input: String,
+    pub part: u8,
+    pub outcome: Outcome,
+    pub verdict: Verdict,
+}
+
+/// Everything a batch run found, in the order it ran.
+#[derive(Debug, Clone)]
+pub struct Batch {
+    pub day: u8,
+    pub rows: Vec<Row>,
+    /// Answers files that exist but could not be read.
+    pub errors: Vec<String>,
+}
+
+impl Batch {
+    /// Rows whose answer differs from a known one, and runs that gave no answer
+    /// whether or not one is known.
+    pub fn failures(&self) -> impl Iterator<Item = &Row> {
+        self.rows.iter().filter(|row| {
+            matches!(row.verdict, Verdict::Wrong { .. })
+                || !matches!(row.outcome, Outcome::Solved { .. })
+        })
+    }
+}
+
+/// Solves every part in `solutions` for every input in `dir`, with `solve`
+/// running one part on one input file.
+pub fn run(
+    day: u8,
+    solutions: &[&Solution],
+    dir: &Path,
+    mut solve: impl FnMut(&Solution, &Path) -> Outcome,
+) -> io::Result<Batch> {
+    let mut batch = Batch {
+        day,
+        rows: Vec::new(),
+        errors: Vec::new(),
+    };
+    for input in inputs(dir)? {
+        let answers_path = answers_path(&input);
+        let answers = Answers::load(&answers_path).unwrap_or_else(|e| {
+            batch
+                .errors
+                .push(format!("{}: {}", answers_path.display(), e));
+            Answers::default()
+        });
+        let name = input.file_name().unwrap().to_string_lossy().into_owned();
+        for solution in solutions {
+            let outcome = solve(solution, &input);
+            let verdict = answers.check(day, solution.part, outcome.answer());
+            batch.rows.push(Row {
+                input: name.clone(),
+                part: solution.part,
+                outcome,
+                verdict,
+            });
+        }
+    }
+    Ok(batch)
+}
+
+impl fmt::Display for Batch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let width = self
+            .rows
+            .iter()
+            .map(|row| row.input.len())
+            .chain([5])
+            .max()
+            .unwrap();
+        writeln!(
+            f,
+            "{:<width$}  part  {:>16}  {:>10}  status",
+            "input", "answer", "time"
+        )?;
+        for row in &self.rows {
+            let (answer, time) = match &row.outcome {
+                Outcome::Solved { answer, elapsed } => {
+                    (answer.to_string(), format!("{:.2?}", elapsed))
+                }
+                other => (other.to_string(), "-".to_string()),
+            };
+            let status = match row.verdict {
+                Verdict::Correct => "correct".to_string(),
+                Verdict::Wrong { expected } => format!("expected {}", expected),
+                Verdict::Unknown => "unverified".to_string(),
+            };
+            writeln!(
+                f,
+                "{:<width$}  {:>4}  {:>16}  {:>10}  {}",
+                row.input, row.part, answer, time, status
+            )?;
+        }
+        for error in &self.errors {
+            writeln!(f, "{}", error)?;
+        }
+        let failures = self.failures().collect::<Vec<_>>();
+        if failures.is_empty() {
+            let checked = self
+                .rows
+                .iter()
+                .filter(|row| row.verdict == Verdict::Correct)
+                .count();
+            return write!(
+                f,
+                "{} of {} answers checked, all correct",
+                checked,
+                self.rows.len()
+            );
+        }
+        write!(f, "Failures ({}):", failures.len())?;
+        for row in &failures {
+            let got = row
+                .outcome
+                .answer()
+                .map_or_else(|| row.outcome.to_string(), |a| a.to_string());
+            write!(
+                f,
+                "\n  {} day {} part {}: got {}",
+                row.input, self.day, row.part, got
+            )?;
+            if let Verdict::Wrong { expected } = row.verdict {
+                write!(f, ", expected {}", expected)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+    use crate::{registry, runner};
+
+    #[test]
+    fn test_batch() {
+        let dir = std::env::temp_dir().join(format!("advent-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let example = std::fs::read_to_string("data/test_input4.txt").unwrap();
+        std::fs::write(dir.join("alice.txt"), &example).unwrap();
+        std::fs::write(dir.join("alice.answers"), "4 1 13\n4 2 30\n").unwrap();
+        std::fs::write(dir.join("bob.txt"), &example).unwrap();
+        std::fs::write(dir.join("bob.answers"), "4 2 31\n").unwrap();
+        std::fs::write(dir.join("carol.txt"), "Card 1: 1 | 2\n").unwrap();
+        std::fs::write(dir.join("dave.txt"), "Card 1: 1 | 2\n").unwrap();
+        std::fs::write(dir.join(".hidden"), "").unwrap();
+
+        let solutions = registry::for_day(4).collect::<Vec<_>>();
+        let batch = run(4, &solutions, &dir, |solution, input| {
+            // Dave has no answers file, so only the outcome shows the run failed.
+            if input.ends_with("dave.txt") {
+                return Outcome::Panic("boom".to_string());
+            }
+            let input = std::fs::read_to_string(input).unwrap();
+            runner::run(solution, &input, &mut NoProgress)
+        })
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(batch.rows.len(), 8);
+        let verdicts = batch.rows.iter().map(|r| r.verdict).collect::<Vec<_>>();
+        assert_eq!(
+            verdicts,
+            vec![
+                Verdict::Correct,
+                Verdict::Correct,
+                Verdict::Unknown,
+                Verdict::Wrong { expected: 31 },
+                Verdict::Unknown,
+                Verdict::Unknown,
+                Verdict::Unknown,
+                Verdict::Unknown,
+            ]
+        );
+        assert_eq!(batch.rows[4].outcome.answer(), Some(0));
+        let failures = batch.failures().collect::<Vec<_>>();
+        assert_eq!(failures.len(), 3);
+        assert_eq!(
+            (failures[0].input.as_str(), failures[0].part),
+            ("bob.txt", 2)
+        );
+        assert!(batch.to_string().ends_with(
+            "Failures (3):\n  bob.txt day 4 part 2: got 30, expected 31\n  \
+             dave.txt day 4 part 1: got panicked: boom\n  \
+             dave.txt day 4 part 2: got panicked: boom"
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+//! Answers of earlier runs, so unchanged solvers are not re-run on unchanged
+//! inputs.
+//!
+//! An entry is only reused for the same day, part, solver variant, input content
+//! and build of the binary, so any code change invalidates every entry.
+
+use crate::runner::Outcome;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const CACHE_PATH: &str = ".advent/cache.jsonl";
+
+/// What a cached answer was computed from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Key {
+    pub day: u8,
+    pub part: u8,
+    pub input_hash: String,
+    pub variant: String,
+    pub build: String,
+}
+
+/// One line of the cache file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    #[serde(flatten)]
+    key: Key,
+    answer: usize,
+    duration_ns: u64,
+}
+
+/// Hash of the sources and manifest this binary was built from, computed by
+/// the build script, so it changes with every change to the code.
+pub const BUILD_ID: &str = env!("ADVENT_BUILD_ID");
+
+/// The cache file's entries, with the latest one winning for each key.
+#[derive(Debug)]
+pub struct Cache {
+    path: PathBuf,
+    entries: HashMap<Key, (usize, Duration)>,
+}
+
+impl Cache {
+    /// Reads the cache at `path`; a missing file is an empty cache.
+    pub fn load(path: &Path) -> io::Result<Cache> {
+        let mut entries = HashMap::new();
+        let file = match fs::File::open(path) {
+            Ok(file) => Some(file),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        for line in file.into_iter().flat_map(|f| BufReader::new(f).lines()) {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: Entry = serde_json::from_str(&line)?;
+            let elapsed = Duration::from_nanos(entry.duration_ns);
+            entries.insert(entry.key, (entry.answer, elapsed));
+        }
+        Ok(Cache {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// The cached outcome for `key`, with the time the original run took.
+    pub fn get(&self, key: &Key) -> Option<Outcome> {
+        let &(answer, elapsed) = self.entries.get(key)?;
+        Some(Outcome::Solved { answer, elapsed })
+    }
+
+    /// Remembers a solved outcome and appends it to the cache file. Anything
+    /// else is not cached, so failures are retried next time.
+    pub fn insert(&mut self, key: Key, outcome: &Outcome) -> io::Result<()> {
+        let Outcome::Solved { answer, elapsed } = *outcome else {
+            return Ok(());
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let entry = Entry {
+            key: key.clone(),
+            answer,
+            duration_ns: elapsed.as_nanos() as u64,
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        self.entries.insert(key, (answer, elapsed));
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Deletes the cache file, returning whether there was one.
+pub fn clear(path: &Path) -> io::Result<bool> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(input_hash: &str, build: &str) -> Key {
+        Key {
+            day: 5,
+            part: 2,
+            input_hash: input_hash.to_string(),
+            variant: "default".to_string(),
+            build: build.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("advent-cache-{}", std::process::id()));
+        let path = dir.join("cache.jsonl");
+        let mut cache = Cache::load(&path).unwrap();
+        assert!(cache.is_empty());
+
+        let solved = Outcome::Solved {
+            answer: 46,
+            elapsed: Duration::from_millis(3),
+        };
+        cache.insert(key("abc", "b1"), &solved).unwrap();
+        cache
+            .insert(key("def", "b1"), &Outcome::Panic("boom".to_string()))
+            .unwrap();
+        let newer = Outcome::Solved {
+            answer: 47,
+            elapsed: Duration::from_millis(1),
+        };
+        cache.insert(key("abc", "b1"), &newer).unwrap();
+
+        let reloaded = Cache::load(&path).unwrap();
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded.get(&key("abc", "b1")), Some(newer));
+        assert_eq!(reloaded.get(&key("abc", "b2")), None);
+        assert_eq!(reloaded.get(&key("def", "b1")), None);
+
+        assert!(clear(&path).unwrap());
+        assert!(!clear(&path).unwrap());
+        assert!(Cache::load(&path).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
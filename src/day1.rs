@@ -1,3 +1,6 @@
+use crate::stream;
+use std::io::{self, BufRead};
+
 fn find_digits(line: &str, day_2: bool) -> u32 {
     let mut digits: Vec<char> = Vec::new();
     for (i, c) in line.chars().enumerate() {
@@ -90,6 +93,13 @@ pub fn find_solution(s: &str, day_2: bool) -> u32 {
     sum
 }
 
+/// Like `find_solution`, but reads one line at a time from `reader`.
+pub fn find_solution_reader(reader: impl BufRead, day_2: bool) -> io::Result<u32> {
+    let mut sum = 0;
+    stream::for_each_line(reader, |line| sum += find_digits(line, day_2))?;
+    Ok(sum)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +110,13 @@ mod tests {
         assert_eq!(find_solution(&input, false), 56465);
         assert_eq!(find_solution(&input, true), 55902);
     }
+
+    #[test]
+    fn test_reader_matches_str() {
+        let input = std::fs::read_to_string("data/input1.txt").unwrap();
+        for day_2 in [false, true] {
+            let streamed = find_solution_reader(input.as_bytes(), day_2).unwrap();
+            assert_eq!(streamed, find_solution(&input, day_2));
+        }
+    }
 }
@@ -1,5 +1,7 @@
+use crate::stream;
 use regex::Regex;
 use std::collections::HashMap;
+use std::io::{self, BufRead};
 
 #[derive(Debug)]
 pub struct GameSet {
@@ -95,6 +97,22 @@ pub fn find_solution(input: &str, exercise2: bool) -> usize {
     }
 }
 
+/// Like `find_solution`, but reads one game at a time from `reader`.
+pub fn find_solution_reader(reader: impl BufRead, exercise2: bool) -> io::Result<usize> {
+    let mut sum = 0;
+    stream::for_each_line(reader, |line| {
+        let game = Game::from_line(line);
+        if !exercise2 {
+            if game.is_possible(12, 13, 14) {
+                sum += game.id;
+            }
+        } else {
+            sum += game.min_possible().power();
+        }
+    })?;
+    Ok(sum)
+}
+
 pub fn solve(exercise2: bool) -> usize {
     let input = std::fs::read_to_string("data/input2.txt").unwrap();
     find_solution(&input, exercise2)
@@ -128,4 +146,16 @@ mod tests {
         let s = solve(true);
         assert_eq!(s, 83105);
     }
+
+    #[test]
+    fn test_reader() {
+        assert_eq!(
+            find_solution_reader(TEST_INPUT.as_bytes(), false).unwrap(),
+            8
+        );
+        assert_eq!(
+            find_solution_reader(TEST_INPUT.as_bytes(), true).unwrap(),
+            2286
+        );
+    }
 }
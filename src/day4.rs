@@ -1,5 +1,8 @@
+use crate::lint::LintReport;
+use crate::stream;
 use regex::Regex;
-use std::collections::{HashSet, HashMap};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, BufRead};
 
 #[derive(Debug)]
 pub struct Card {
@@ -46,7 +49,10 @@ impl Card {
     }
 
     pub fn num_winning(&self) -> usize {
-        self.your_nums.iter().filter(|n| self.winning_nums.contains(n)).count()
+        self.your_nums
+            .iter()
+            .filter(|n| self.winning_nums.contains(n))
+            .count()
     }
 
     pub fn points_worth(&self) -> u32 {
@@ -59,7 +65,7 @@ impl Card {
     }
 }
 
-fn parse_input(text: &str) -> Vec<Card> {
+pub fn parse_input(text: &str) -> Vec<Card> {
     let mut cards = Vec::new();
     for line in text.lines() {
         cards.push(Card::from_line(line));
@@ -72,17 +78,84 @@ pub fn solve(input: &str) -> u32 {
     cards.iter().map(|c| c.points_worth()).sum()
 }
 
+// Copies won of cards past the end of the deck are counted but never summed, so
+// a card near the end wins only the cards that exist, as in solve_star2_array.
 pub fn solve_star2(input: &str) -> u32 {
     let cards = parse_input(input);
-    let mut card_counts = HashMap::<usize,usize>::new();
-    for card in cards {
+    let mut card_counts = HashMap::<usize, usize>::new();
+    for card in &cards {
         let this_card_count = *card_counts.entry(card.id).or_insert(1);
         let num_winning = card.num_winning();
-        for card_id in card.id+1..=card.id+num_winning {
-            card_counts.entry(card_id).and_modify(|c| *c += this_card_count).or_insert(this_card_count + 1);
+        for card_id in card.id + 1..=card.id + num_winning {
+            card_counts
+                .entry(card_id)
+                .and_modify(|c| *c += this_card_count)
+                .or_insert(this_card_count + 1);
         }
     }
-    card_counts.values().sum::<usize>() as u32
+    cards
+        .iter()
+        .map(|card| card_counts[&card.id])
+        .sum::<usize>() as u32
+}
+
+// Same as solve_star2, but counts copies in a Vec indexed by position. Cards
+// only ever win copies of cards after them, so one pass in order is enough.
+pub fn solve_star2_array(input: &str) -> u32 {
+    let cards = parse_input(input);
+    let mut copies = vec![1usize; cards.len()];
+    for (i, card) in cards.iter().enumerate() {
+        let won = (i + card.num_winning()).min(cards.len() - 1);
+        for j in i + 1..=won {
+            copies[j] += copies[i];
+        }
+    }
+    copies.iter().sum::<usize>() as u32
+}
+
+/// Like `solve`, but reads one card at a time from `reader`.
+pub fn solve_reader(reader: impl BufRead) -> io::Result<u32> {
+    let mut sum = 0;
+    stream::for_each_line(reader, |line| sum += Card::from_line(line).points_worth())?;
+    Ok(sum)
+}
+
+// Like solve_star2_array, but only keeps the copies won of the next few cards:
+// `pending[k]` is how many extra copies the card k places ahead has won so far,
+// so memory is bounded by the most matches on any one card. Copies won past the
+// last card are dropped, as in solve_star2_array.
+pub fn solve_star2_reader(reader: impl BufRead) -> io::Result<u32> {
+    let mut pending = VecDeque::<usize>::new();
+    let mut total = 0;
+    stream::for_each_line(reader, |line| {
+        let copies = 1 + pending.pop_front().unwrap_or(0);
+        total += copies;
+        let won = Card::from_line(line).num_winning();
+        if pending.len() < won {
+            pending.resize(won, 0);
+        }
+        for count in pending.iter_mut().take(won) {
+            *count += copies;
+        }
+    })?;
+    Ok(total as u32)
+}
+
+/// Lint hook for the registry: checks cards are numbered by position, which
+/// both part 2 solvers assume.
+pub fn lint(input: &str) -> LintReport {
+    let cards = parse_input(input);
+    let mut problems = Vec::new();
+    for (i, card) in cards.iter().enumerate() {
+        if card.id != i + 1 {
+            problems.push(format!("card {} is in position {}", card.id, i + 1));
+        }
+    }
+    let most = cards.iter().map(Card::num_winning).max().unwrap_or(0);
+    LintReport {
+        problems,
+        notes: vec![format!("{} cards, at most {} matches", cards.len(), most)],
+    }
 }
 
 #[cfg(test)]
@@ -115,6 +188,40 @@ mod tests {
     fn test_star2_test_input() {
         let input = std::fs::read_to_string("data/test_input4.txt").unwrap();
         assert_eq!(solve_star2(&input), 30);
+        assert_eq!(solve_star2_array(&input), 30);
+    }
+
+    #[test]
+    fn test_reader_matches_str() {
+        for path in ["data/test_input4.txt", "data/input4.txt"] {
+            let input = std::fs::read_to_string(path).unwrap();
+            assert_eq!(solve_reader(input.as_bytes()).unwrap(), solve(&input));
+            assert_eq!(
+                solve_star2_reader(input.as_bytes()).unwrap(),
+                solve_star2_array(&input)
+            );
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        let input = std::fs::read_to_string("data/test_input4.txt").unwrap();
+        assert!(lint(&input).problems.is_empty());
+        let shifted = input.lines().skip(1).collect::<Vec<_>>().join("\n");
+        let problems = lint(&shifted).problems;
+        assert_eq!(problems.len(), 5);
+        assert_eq!(problems[0], "card 2 is in position 1");
+        let truncated = input.lines().take(4).collect::<Vec<_>>().join("\n");
+        assert!(lint(&truncated).problems.is_empty());
+    }
+
+    #[test]
+    fn test_star2_copies_past_the_end() {
+        // Card 1 wins copies of cards 2 and 3, but there is no card 3.
+        let input = "Card 1: 1 2 | 1 2\nCard 2: 3 | 4";
+        assert_eq!(solve_star2(input), 3);
+        assert_eq!(solve_star2_array(input), 3);
+        assert_eq!(solve_star2_reader(input.as_bytes()).unwrap(), 3);
     }
 
     #[test]
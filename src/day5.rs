@@ -1,4 +1,12 @@
+use crate::error::SolveError;
+use crate::interval::{Interval, IntervalSet};
+use crate::lint::LintReport;
+use crate::progress::{Cancelled, Event, Progress, ProgressBar};
+use core::fmt;
 use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Formatter;
+use thiserror::Error;
 
 #[derive(Debug)]
 pub struct Seeds {
@@ -24,24 +32,33 @@ impl Seeds {
         Seeds { seeds }
     }
 
-    pub fn min_location_number(&self, maps: &Vec<Map>) -> usize {
+    pub fn min_location_number(
+        &self,
+        maps: &[&Map],
+        progress: &mut dyn Progress,
+    ) -> Result<usize, Cancelled> {
         let mut min_location_number = usize::MAX;
         let n = self.seeds.len();
         for (i, seed) in self.seeds.iter().enumerate() {
-            if i % 10000000 == 0 {
-                // Show the number in % of total seeds processed
-                println!("{}% done", i * 100 / n);
-                println!("current min location number: {}", min_location_number);
+            if i % 1000000 == 0 {
+                if min_location_number != usize::MAX {
+                    progress.event(Event::Value {
+                        name: "min".to_string(),
+                        value: min_location_number,
+                    });
+                }
+                progress.step(i, n)?;
             }
             let location_number = Seeds::location_number(*seed, maps);
             if location_number < min_location_number {
                 min_location_number = location_number;
             }
         }
-        min_location_number
+        progress.step(n, n)?;
+        Ok(min_location_number)
     }
 
-    pub fn location_number(seed: usize, maps: &Vec<Map>) -> usize {
+    pub fn location_number(seed: usize, maps: &[&Map]) -> usize {
         let mut number = seed;
         for map in maps {
             number = map.apply(number);
@@ -52,27 +69,52 @@ impl Seeds {
     pub fn expand_ranges(self) -> Seeds {
         let mut seeds = Vec::new();
         let mut seded_iter = self.seeds.iter();
-        assert!(self.seeds.len() % 2 == 0);
+        assert!(self.seeds.len().is_multiple_of(2));
         while let Some(start) = seded_iter.next() {
             let length = seded_iter.next().unwrap();
-            for i in *start..=*start + *length {
+            for i in *start..*start + *length {
                 seeds.push(i);
             }
         }
         Seeds { seeds }
     }
+
+    /// The seed numbers read as (start, length) pairs, as half-open intervals.
+    pub fn intervals(&self) -> Vec<(usize, usize)> {
+        self.seeds
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[0] + pair[1]))
+            .collect()
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Range {
-    source_start: usize,
-    destination_start: usize,
-    length: usize,
+    pub source_start: usize,
+    pub destination_start: usize,
+    pub length: usize,
+}
+
+// `start + length`, or `usize::MAX` for a range that runs past it, which
+// `Diagnostic::Overflow` reports.
+fn end(start: usize, length: usize) -> usize {
+    start.saturating_add(length)
 }
 
 impl Range {
     pub fn in_range(&self, number: usize) -> bool {
-        number >= self.source_start && number < self.source_start + self.length
+        number >= self.source_start && number - self.source_start < self.length
+    }
+
+    pub fn source(&self) -> Interval<usize> {
+        Interval::new(self.source_start, end(self.source_start, self.length))
+    }
+
+    pub fn destination(&self) -> Interval<usize> {
+        Interval::new(
+            self.destination_start,
+            end(self.destination_start, self.length),
+        )
     }
 
     pub fn apply(&self, number: usize) -> usize {
@@ -124,15 +166,460 @@ impl Map {
     }
 
     pub fn apply(&self, number: usize) -> usize {
+        match self.matching_range(number) {
+            Some(range) => range.apply(number),
+            None => number,
+        }
+    }
+
+    /// The range `apply` uses for `number`, or `None` if it maps to itself.
+    pub fn matching_range(&self, number: usize) -> Option<&Range> {
+        self.ranges.iter().find(|range| range.in_range(number))
+    }
+
+    /// Splits the half-open interval `[start, end)` wherever the ranges cut it.
+    ///
+    /// Returns (source piece, destination piece) pairs sorted by source. Like
+    /// `apply`, the first matching range wins and unmatched numbers map to themselves.
+    pub fn split_interval(
+        &self,
+        (start, end): (usize, usize),
+    ) -> Vec<((usize, usize), (usize, usize))> {
+        let mut unmapped = IntervalSet::from(Interval::new(start, end));
+        let mut pieces = Vec::new();
         for range in &self.ranges {
-            if range.in_range(number) {
-                return range.apply(number);
+            let source = IntervalSet::from(range.source());
+            let matched = unmapped.intersection(&source);
+            for piece in matched.iter() {
+                let destination = piece.shift(range.source_start, range.destination_start);
+                pieces.push(((*piece).into(), destination.into()));
             }
+            unmapped = unmapped.difference(&source);
         }
-        number
+        pieces.extend(unmapped.iter().map(|&piece| (piece.into(), piece.into())));
+        pieces.sort();
+        pieces
+    }
+
+    /// Maps the half-open interval `[start, end)` and returns the pieces it lands on.
+    pub fn apply_interval(&self, interval: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut mapped = self
+            .split_interval(interval)
+            .into_iter()
+            .map(|(_, destination)| destination)
+            .collect::<Vec<_>>();
+        mapped.sort();
+        mapped
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
+    }
+
+    // Source and destination intervals of every well-formed range.
+    fn intervals(&self) -> Vec<((usize, usize), (usize, usize))> {
+        self.ranges
+            .iter()
+            .filter(|r| r.length > 0)
+            .filter_map(|r| {
+                let source_end = r.source_start.checked_add(r.length)?;
+                let destination_end = r.destination_start.checked_add(r.length)?;
+                Some((
+                    (r.source_start, source_end),
+                    (r.destination_start, destination_end),
+                ))
+            })
+            .collect()
+    }
+
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (index, range) in self.ranges.iter().enumerate() {
+            if range.length == 0 {
+                diagnostics.push(Diagnostic::EmptyRange {
+                    map: self.name(),
+                    index,
+                });
+            }
+            if range.source_start.checked_add(range.length).is_none()
+                || range.destination_start.checked_add(range.length).is_none()
+            {
+                diagnostics.push(Diagnostic::Overflow {
+                    map: self.name(),
+                    index,
+                });
+            }
+        }
+        for (first, a) in self.ranges.iter().enumerate() {
+            for (second, b) in self.ranges.iter().enumerate().skip(first + 1) {
+                let (Some(a_end), Some(b_end)) = (
+                    a.source_start.checked_add(a.length),
+                    b.source_start.checked_add(b.length),
+                ) else {
+                    continue;
+                };
+                if a.source_start < b_end && b.source_start < a_end {
+                    diagnostics.push(Diagnostic::Overlap {
+                        map: self.name(),
+                        first,
+                        second,
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+
+    pub fn coverage(&self) -> Coverage {
+        let intervals = self.intervals();
+        let sources = intervals
+            .iter()
+            .map(|(s, _)| Interval::from(*s))
+            .collect::<IntervalSet<_>>();
+        let destination_total = intervals.iter().map(|(_, (s, e))| e - s).sum::<usize>();
+        let destinations = intervals
+            .iter()
+            .map(|(_, d)| Interval::from(*d))
+            .collect::<IntervalSet<_>>();
+        let injective = destinations.len() == destination_total;
+        Coverage {
+            map: self.name(),
+            mapped: sources.len(),
+            domain: sources.intervals().last().map_or(0, |i| i.end),
+            injective,
+            bijective: injective && sources == destinations,
+        }
+    }
+}
+
+/// A problem in an almanac that would make `Map::apply` unreliable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Two ranges claim the same source numbers, so the answer depends on their order.
+    Overlap {
+        map: String,
+        first: usize,
+        second: usize,
+    },
+    EmptyRange {
+        map: String,
+        index: usize,
+    },
+    /// `start + length` does not fit in a `usize`.
+    Overflow {
+        map: String,
+        index: usize,
+    },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::Overlap { map, first, second } => write!(
+                f,
+                "{}: ranges {} and {} have overlapping sources",
+                map, first, second
+            ),
+            Diagnostic::EmptyRange { map, index } => {
+                write!(f, "{}: range {} has length zero", map, index)
+            }
+            Diagnostic::Overflow { map, index } => {
+                write!(
+                    f,
+                    "{}: range {} overflows when adding its length",
+                    map, index
+                )
+            }
+        }
+    }
+}
+
+/// How much of its domain a map covers and whether it is one-to-one there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub map: String,
+    /// Numbers in `[0, domain)` that some range maps.
+    pub mapped: usize,
+    /// End of the highest source range.
+    pub domain: usize,
+    /// No two ranges send numbers to the same destination.
+    pub injective: bool,
+    /// Injective, and the ranges' destinations are exactly their sources.
+    pub bijective: bool,
+}
+
+impl Coverage {
+    pub fn fraction(&self) -> f64 {
+        if self.domain == 0 {
+            return 0.0;
+        }
+        self.mapped as f64 / self.domain as f64
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let shape = if self.bijective {
+            "bijection on its ranges"
+        } else if self.injective {
+            "injective, not a bijection on its ranges"
+        } else {
+            "not injective"
+        };
+        write!(
+            f,
+            "{}: {:.1}% of [0, {}) mapped, {}",
+            self.map,
+            self.fraction() * 100.0,
+            self.domain,
+            shape
+        )
     }
 }
 
+pub fn validate(maps: &[Map]) -> Vec<Diagnostic> {
+    maps.iter().flat_map(|map| map.validate()).collect()
+}
+
+/// Lint hook for the registry: diagnostics and maps that do not chain from
+/// seed to location are problems, coverage lines are notes.
+pub fn lint(input: &str) -> LintReport {
+    let (_seeds, maps) = parse_input(input);
+    let mut problems = validate(&maps)
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>();
+    let notes = maps.iter().map(|m| m.coverage().to_string()).collect();
+    let chain = Almanac::new(maps).and_then(|almanac| almanac.seed_to_location().map(|_| ()));
+    match chain {
+        // Already among the diagnostics above.
+        Ok(()) | Err(AlmanacError::Overflow(_)) => (),
+        Err(e) => problems.push(e.to_string()),
+    }
+    LintReport { problems, notes }
+}
+
+/// How one map converted a number on its way from seed to location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep<'a> {
+    pub source: &'a str,
+    pub destination: &'a str,
+    pub input: usize,
+    pub output: usize,
+    /// The range that matched, or `None` when the number mapped to itself.
+    pub range: Option<&'a Range>,
+}
+
+impl fmt::Display for TraceStep<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} -> {} {} ",
+            self.source, self.input, self.destination, self.output
+        )?;
+        match self.range {
+            Some(range) => write!(
+                f,
+                "(range {} {} {})",
+                range.destination_start, range.source_start, range.length
+            ),
+            None => write!(f, "(identity)"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum AlmanacError {
+    #[error("two maps convert {from} to {to}")]
+    DuplicateMap { from: String, to: String },
+    #[error("no chain of maps converts {from} to {to}")]
+    NoPath { from: String, to: String },
+    #[error("{count} different chains of maps convert {from} to {to}")]
+    Ambiguous {
+        from: String,
+        to: String,
+        count: usize,
+    },
+    #[error("the maps between {from} and {to} form a cycle")]
+    Cycle { from: String, to: String },
+    /// Converting through the range could produce a number past `usize::MAX`.
+    #[error("{0}")]
+    Overflow(Diagnostic),
+}
+
+impl From<AlmanacError> for SolveError {
+    fn from(e: AlmanacError) -> SolveError {
+        SolveError::Invalid(e.to_string())
+    }
+}
+
+/// The maps of an almanac as a directed graph between categories.
+///
+/// Conversions are found by following maps from category to category, so the
+/// order the maps appear in the input does not matter.
+#[derive(Debug)]
+pub struct Almanac {
+    pub maps: Vec<Map>,
+}
+
+impl Almanac {
+    pub fn new(maps: Vec<Map>) -> Result<Almanac, AlmanacError> {
+        for (i, a) in maps.iter().enumerate() {
+            if maps[i + 1..]
+                .iter()
+                .any(|b| a.source == b.source && a.destination == b.destination)
+            {
+                return Err(AlmanacError::DuplicateMap {
+                    from: a.source.clone(),
+                    to: a.destination.clone(),
+                });
+            }
+        }
+        let overflow = maps
+            .iter()
+            .flat_map(Map::validate)
+            .find(|d| matches!(d, Diagnostic::Overflow { .. }));
+        if let Some(diagnostic) = overflow {
+            return Err(AlmanacError::Overflow(diagnostic));
+        }
+        Ok(Almanac { maps })
+    }
+
+    // Categories reachable from `start` along the maps, or against them when
+    // `forward` is false. Maps out of `to` are ignored, since a chain ends there.
+    fn reachable<'a>(&'a self, start: &'a str, to: &str, forward: bool) -> HashSet<&'a str> {
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(category) = queue.pop_front() {
+            for map in self.maps.iter().filter(|map| map.source != to) {
+                let (here, next) = match forward {
+                    true => (&map.source, &map.destination),
+                    false => (&map.destination, &map.source),
+                };
+                if here == category && seen.insert(next.as_str()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// The maps that convert `from` into `to`, in the order they must be applied.
+    ///
+    /// Fails if no chain of maps connects the categories, or if several do, since
+    /// different chains could disagree.
+    ///
+    /// Chains are counted in one topological walk over the maps that lie on some
+    /// chain, so the work depends on the number of maps, not of chains.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<&Map>, AlmanacError> {
+        let ahead = self.reachable(from, to, true);
+        let behind = self.reachable(to, to, false);
+        if !ahead.contains(to) {
+            return Err(AlmanacError::NoPath {
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+        let on_chain = self
+            .maps
+            .iter()
+            .filter(|map| {
+                map.source != to
+                    && ahead.contains(map.source.as_str())
+                    && behind.contains(map.destination.as_str())
+            })
+            .collect::<Vec<_>>();
+
+        let mut incoming = HashMap::<&str, usize>::new();
+        for map in &on_chain {
+            *incoming.entry(&map.destination).or_default() += 1;
+        }
+        // Every other category on a chain is reached from `from`, so a map into
+        // `from` closes a cycle, as does any category the walk never frees.
+        let cycle = || AlmanacError::Cycle {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        if incoming.contains_key(from) {
+            return Err(cycle());
+        }
+        let categories = ahead.intersection(&behind).count();
+        let mut chains = HashMap::from([(from, 1usize)]);
+        let mut queue = VecDeque::from([from]);
+        let mut visited = 0;
+        while let Some(category) = queue.pop_front() {
+            visited += 1;
+            let count = chains.get(category).copied().unwrap_or(0);
+            for map in on_chain.iter().filter(|map| map.source == category) {
+                let next = map.destination.as_str();
+                let total = chains.entry(next).or_default();
+                *total = total.saturating_add(count);
+                let remaining = incoming.get_mut(next).unwrap();
+                *remaining -= 1;
+                if *remaining == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+        if visited < categories {
+            return Err(cycle());
+        }
+        match chains[to] {
+            1 => (),
+            count => {
+                return Err(AlmanacError::Ambiguous {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    count,
+                })
+            }
+        }
+
+        // With a single chain, every category on it has exactly one map into it.
+        let mut path = Vec::new();
+        let mut category = to;
+        while category != from {
+            let map = on_chain
+                .iter()
+                .find(|map| map.destination == category)
+                .unwrap();
+            path.push(*map);
+            category = &map.source;
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    pub fn convert(&self, from: &str, to: &str, number: usize) -> Result<usize, AlmanacError> {
+        let path = self.path(from, to)?;
+        Ok(path.iter().fold(number, |number, map| map.apply(number)))
+    }
+
+    /// The seed-to-location chain every solver uses.
+    pub fn seed_to_location(&self) -> Result<Vec<&Map>, AlmanacError> {
+        self.path("seed", "location")
+    }
+}
+
+/// Every intermediate value of `seed` as it passes through `maps`, in order.
+pub fn trace<'a>(seed: usize, maps: &[&'a Map]) -> Vec<TraceStep<'a>> {
+    let mut number = seed;
+    let mut steps = Vec::new();
+    for map in maps {
+        let range = map.matching_range(number);
+        let output = map.apply(number);
+        steps.push(TraceStep {
+            source: &map.source,
+            destination: &map.destination,
+            input: number,
+            output,
+            range,
+        });
+        number = output;
+    }
+    steps
+}
+
 pub fn parse_input(input: &str) -> (Seeds, Vec<Map>) {
     let mut maps = Vec::new();
     let mut input_iter = input.split("\n\n");
@@ -146,29 +633,98 @@ pub fn parse_input(input: &str) -> (Seeds, Vec<Map>) {
     (seeds, maps)
 }
 
-pub fn solve(input: &str) -> usize {
+pub fn solve(input: &str, progress: &mut dyn Progress) -> Result<usize, SolveError> {
     let (seeds, maps) = parse_input(input);
-    seeds.min_location_number(&maps)
+    let almanac = Almanac::new(maps)?;
+    let maps = almanac.seed_to_location()?;
+    progress.event(Event::Note(format!(
+        "mapping {} seeds through {} maps",
+        seeds.seeds.len(),
+        maps.len()
+    )));
+    progress.event(Event::Stage(
+        "finding the minimum location number".to_string(),
+    ));
+    Ok(seeds.min_location_number(&maps, progress)?)
 }
 
-pub fn solve_star2(input: &str) -> usize {
+pub fn solve_star2(input: &str, progress: &mut dyn Progress) -> Result<usize, SolveError> {
     let (seeds, maps) = parse_input(input);
+    let almanac = Almanac::new(maps)?;
+    let maps = almanac.seed_to_location()?;
 
+    progress.event(Event::Stage("expanding seed ranges".to_string()));
+    progress.event(Event::Note(format!(
+        "expanding {} seed ranges",
+        seeds.seeds.len() / 2
+    )));
     let seeds = seeds.expand_ranges();
-    println!("{} seeds expanded!", seeds.seeds.len());
-    println!("Finding the minimum location number...");
+    progress.event(Event::Value {
+        name: "seeds".to_string(),
+        value: seeds.seeds.len(),
+    });
+
+    progress.event(Event::Stage(
+        "finding the minimum location number".to_string(),
+    ));
+    Ok(seeds.min_location_number(&maps, progress)?)
+}
+
+// Same answer as solve_star2, but maps whole seed intervals through each map
+// instead of every seed on its own.
+pub fn solve_star2_intervals(
+    input: &str,
+    progress: &mut dyn Progress,
+) -> Result<usize, SolveError> {
+    let (seeds, maps) = parse_input(input);
+    let almanac = Almanac::new(maps)?;
+    let maps = almanac.seed_to_location()?;
 
-    seeds.min_location_number(&maps)
+    progress.event(Event::Stage("mapping seed intervals".to_string()));
+    let mut intervals = seeds.intervals();
+    for (i, map) in maps.iter().enumerate() {
+        progress.step(i, maps.len())?;
+        intervals = intervals
+            .into_iter()
+            .flat_map(|interval| map.apply_interval(interval))
+            .collect();
+    }
+    progress.step(maps.len(), maps.len())?;
+    Ok(intervals
+        .iter()
+        .map(|&(start, _)| start)
+        .min()
+        .unwrap_or(usize::MAX))
 }
 
 pub fn main() {
-    // let input = std::fs::rad_to_string("data/input5.txt").unwrap();
-    // println!("Day 5: {}", solve_star2(&input));
+    let input = std::fs::read_to_string("data/input5.txt").unwrap();
+    let mut bar = ProgressBar::new();
+    let result = solve_star2(&input, &mut bar);
+    bar.finish();
+    match result {
+        Ok(result) => println!("Day 5: {}", result),
+        Err(e) => println!("Day 5: {}", e),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::progress::NoProgress;
+
+    #[test]
+    fn test_expand_ranges_excludes_range_end() {
+        // "79 14" is the 14 seeds 79 to 92, so 93 and 68 are not seeds.
+        let seeds = Seeds {
+            seeds: vec![79, 14, 55, 13],
+        }
+        .expand_ranges();
+        assert_eq!(seeds.seeds.len(), 27);
+        assert_eq!(seeds.seeds[..2], [79, 80]);
+        assert!(seeds.seeds.contains(&92) && seeds.seeds.contains(&67));
+        assert!(!seeds.seeds.contains(&93) && !seeds.seeds.contains(&68));
+    }
 
     #[test]
     fn test_parse_input_day5() {
@@ -182,21 +738,226 @@ mod tests {
         assert_eq!(maps[0].apply(99), 51);
     }
 
+    #[test]
+    fn test_apply_interval() {
+        let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
+        let (_seeds, maps) = parse_input(&input);
+        // seed-to-soil: 50 98 2 and 52 50 48
+        assert_eq!(maps[0].apply_interval((79, 93)), vec![(81, 95)]);
+        assert_eq!(
+            maps[0].apply_interval((40, 100)),
+            vec![(40, 50), (50, 52), (52, 100)]
+        );
+        assert_eq!(maps[0].apply_interval((0, 10)), vec![(0, 10)]);
+    }
+
+    #[test]
+    fn test_trace_seed_79() {
+        let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
+        let (_seeds, maps) = parse_input(&input);
+        let almanac = Almanac::new(maps).unwrap();
+        let maps = almanac.seed_to_location().unwrap();
+        let steps = trace(79, &maps);
+        let values = steps.iter().map(|s| s.output).collect::<Vec<_>>();
+        assert_eq!(values, vec![81, 81, 81, 74, 78, 78, 82]);
+        assert_eq!(steps[0].range, Some(&maps[0].ranges[1]));
+        assert_eq!(steps[1].range, None);
+        assert_eq!(steps[0].to_string(), "seed 79 -> soil 81 (range 52 50 48)");
+        assert_eq!(steps[1].to_string(), "soil 81 -> fertilizer 81 (identity)");
+    }
+
+    #[test]
+    fn test_validate() {
+        let map = Map::from_entry(&format!(
+            "a-to-b map:\n10 0 5\n20 3 4\n0 30 0\n0 {} 2",
+            usize::MAX
+        ));
+        assert_eq!(
+            map.validate(),
+            vec![
+                Diagnostic::EmptyRange {
+                    map: "a-to-b".to_string(),
+                    index: 2
+                },
+                Diagnostic::Overflow {
+                    map: "a-to-b".to_string(),
+                    index: 3
+                },
+                Diagnostic::Overlap {
+                    map: "a-to-b".to_string(),
+                    first: 0,
+                    second: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coverage() {
+        let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
+        let (_seeds, maps) = parse_input(&input);
+        assert!(validate(&maps).is_empty());
+        // seed-to-soil maps [50, 100) onto itself.
+        let coverage = maps[0].coverage();
+        assert_eq!((coverage.mapped, coverage.domain), (50, 100));
+        assert!(coverage.bijective);
+        // Both ranges send numbers to [2, 5).
+        let coverage = Map::from_entry("a-to-b map:\n0 0 5\n2 10 5").coverage();
+        assert_eq!((coverage.mapped, coverage.domain), (10, 15));
+        assert!(!coverage.injective);
+    }
+
+    #[test]
+    fn test_shuffled_maps() {
+        let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
+        let mut entries = input.trim_end().split("\n\n").collect::<Vec<_>>();
+        entries[1..].reverse();
+        let shuffled = entries.join("\n\n");
+        assert_eq!(solve(&shuffled, &mut NoProgress).unwrap(), 35);
+        let (_seeds, maps) = parse_input(&shuffled);
+        let almanac = Almanac::new(maps).unwrap();
+        assert_eq!(almanac.convert("soil", "humidity", 81), Ok(78));
+        assert_eq!(almanac.convert("seed", "seed", 79), Ok(79));
+    }
+
+    #[test]
+    fn test_almanac_errors() {
+        let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
+        let (_seeds, mut maps) = parse_input(&input);
+        maps.push(Map::from_entry("seed-to-water map:\n0 0 1"));
+        let almanac = Almanac::new(maps).unwrap();
+        assert_eq!(
+            almanac.path("seed", "location").unwrap_err(),
+            AlmanacError::Ambiguous {
+                from: "seed".to_string(),
+                to: "location".to_string(),
+                count: 2
+            }
+        );
+        // Only one chain reaches water from soil, so the branch does not matter there.
+        assert_eq!(almanac.path("soil", "water").unwrap().len(), 2);
+        assert!(matches!(
+            almanac.path("location", "seed"),
+            Err(AlmanacError::NoPath { .. })
+        ));
+
+        let duplicate = vec![
+            Map::from_entry("seed-to-soil map:\n0 0 1"),
+            Map::from_entry("seed-to-soil map:\n1 0 1"),
+        ];
+        assert!(matches!(
+            Almanac::new(duplicate),
+            Err(AlmanacError::DuplicateMap { .. })
+        ));
+    }
+
+    #[test]
+    fn test_almanac_counts_chains_in_dense_graphs() {
+        // Forty-one layers of two categories, each linked to both of the next
+        // layer, give 2^41 chains; counting them must not walk each one.
+        let category = |side: &str, layer: usize| format!("{}{}", side, "x".repeat(layer));
+        let mut maps = Vec::new();
+        for side in ["a", "b"] {
+            maps.push(Map::from_entry(&format!(
+                "seed-to-{} map:\n0 0 1",
+                category(side, 0)
+            )));
+            let last = category(side, 40);
+            maps.push(Map::from_entry(&format!(
+                "{}-to-location map:\n0 0 1",
+                last
+            )));
+        }
+        for layer in 0..40 {
+            for from in ["a", "b"] {
+                for to in ["a", "b"] {
+                    let (from, to) = (category(from, layer), category(to, layer + 1));
+                    maps.push(Map::from_entry(&format!("{}-to-{} map:\n0 0 1", from, to)));
+                }
+            }
+        }
+        let almanac = Almanac::new(maps).unwrap();
+        assert_eq!(
+            almanac.seed_to_location().unwrap_err(),
+            AlmanacError::Ambiguous {
+                from: "seed".to_string(),
+                to: "location".to_string(),
+                count: 1 << 41
+            }
+        );
+
+        let cycle = vec![
+            Map::from_entry("seed-to-soil map:\n0 0 1"),
+            Map::from_entry("soil-to-seed map:\n0 0 1"),
+            Map::from_entry("soil-to-location map:\n0 0 1"),
+        ];
+        assert!(matches!(
+            Almanac::new(cycle).unwrap().seed_to_location(),
+            Err(AlmanacError::Cycle { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_almanac_is_an_error() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n0 0 1\n\nseed-to-soil map:\n1 0 1";
+        let expected = SolveError::Invalid("two maps convert seed to soil".to_string());
+        assert_eq!(solve(input, &mut NoProgress), Err(expected.clone()));
+        assert_eq!(solve_star2(input, &mut NoProgress), Err(expected.clone()));
+        assert_eq!(solve_star2_intervals(input, &mut NoProgress), Err(expected));
+        assert!(lint(input)
+            .problems
+            .contains(&"two maps convert seed to soil".to_string()));
+
+        let overflowing = format!("seeds: 1 2\n\nseed-to-location map:\n0 1 {}", usize::MAX);
+        let problem = "seed-to-location: range 0 overflows when adding its length";
+        assert_eq!(lint(&overflowing).problems, vec![problem.to_string()]);
+        assert_eq!(
+            solve(&overflowing, &mut NoProgress),
+            Err(SolveError::Invalid(problem.to_string()))
+        );
+        let map = Map::from_entry(&format!("a-to-b map:\n0 1 {}", usize::MAX));
+        let range = &map.ranges[0];
+        assert!(range.in_range(usize::MAX) && !range.in_range(0));
+        assert_eq!(range.source(), Interval::new(1, usize::MAX));
+
+        let unchained = "seeds: 1 2\n\nseed-to-soil map:\n0 0 1";
+        assert!(matches!(
+            solve(unchained, &mut NoProgress),
+            Err(SolveError::Invalid(_))
+        ));
+    }
+
     #[test]
     fn test_star1_test_input_day5() {
         let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
-        assert_eq!(solve(&input), 35);
+        assert_eq!(solve(&input, &mut NoProgress).unwrap(), 35);
     }
 
     #[test]
     fn test_star1_real_input_day5() {
         let input = std::fs::read_to_string("data/input5.txt").unwrap();
-        assert_eq!(solve(&input), 51580674);
+        assert_eq!(solve(&input, &mut NoProgress).unwrap(), 51580674);
     }
 
     #[test]
     fn test_star2_test_input() {
         let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
-        assert_eq!(solve_star2(&input), 46);
+        assert_eq!(solve_star2(&input, &mut NoProgress).unwrap(), 46);
+        assert_eq!(solve_star2_intervals(&input, &mut NoProgress).unwrap(), 46);
+    }
+
+    #[test]
+    fn test_star2_reports_progress() {
+        let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
+        let mut events: Vec<Event> = Vec::new();
+        solve_star2(&input, &mut events).unwrap();
+        assert_eq!(events[0], Event::Stage("expanding seed ranges".to_string()));
+        assert_eq!(
+            events.last(),
+            Some(&Event::Step {
+                done: 27,
+                total: 27
+            })
+        );
     }
 }
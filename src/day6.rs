@@ -1,18 +1,66 @@
-use regex::Regex;
+use crate::interval::{Interval, IntervalSet};
+use crate::math::isqrt_u128;
+
+/// How charging the boat turns into distance travelled.
+///
+/// The puzzle's rules are the default: 1 mm/ms of speed per ms charged, no top
+/// speed and no delay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Physics {
+    /// Speed gained per ms of charging, in mm/ms.
+    pub acceleration: usize,
+    /// Speed the boat cannot exceed however long it charges.
+    pub max_speed: Option<usize>,
+    /// Time between releasing the button and the boat starting to move, in ms.
+    pub start_delay: usize,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Physics {
+            acceleration: 1,
+            max_speed: None,
+            start_delay: 0,
+        }
+    }
+}
+
+impl Physics {
+    pub fn speed(&self, charge_time: usize) -> u128 {
+        let speed = self.acceleration as u128 * charge_time as u128;
+        self.max_speed.map_or(speed, |max| speed.min(max as u128))
+    }
+
+    /// Distance travelled in a race of `time` ms after charging for `charge_time`.
+    pub fn distance(&self, time: usize, charge_time: usize) -> u128 {
+        let moving = time.saturating_sub(charge_time + self.start_delay);
+        self.speed(charge_time) * moving as u128
+    }
+}
+
+// The first `c` in `[low, high)` for which `predicate` holds, or `high` if none
+// does. `predicate` must be false up to some point and true after it.
+fn first_true(mut low: usize, mut high: usize, predicate: impl Fn(usize) -> bool) -> usize {
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if predicate(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    low
+}
 
 #[derive(Clone, Debug)]
-struct Race {
+pub struct Race {
     time: usize,
     record: usize,
 }
 
 impl Race {
-
     pub fn new(time: usize, record: usize) -> Self {
-        Self {
-            time,
-            record,
-        }
+        Self { time, record }
     }
 
     pub fn calc_distance(&self, charge_time: usize) -> usize {
@@ -27,18 +75,62 @@ impl Race {
     }
 
     pub fn num_ways_to_beat_record(&self) -> usize {
-        (1..self.time - 1)
+        (1..self.time)
             .filter(|&charge_time| self.beats_record(charge_time))
             .count()
     }
 
+    // The distance c * (time - c) is a parabola symmetric around time / 2, so
+    // the charge times that beat the record are one run around the middle.
+    // Solve for its lower end and mirror it.
+    pub fn num_ways_closed_form(&self) -> usize {
+        let (time, record) = (self.time as u128, self.record as u128);
+        let beats = |c: u128| c * (time - c) > record;
+        if !beats(time / 2) {
+            return 0;
+        }
+        let root = isqrt_u128(time * time - 4 * record);
+        // Rounding the root and halving can each leave the estimate one off.
+        let mut low = (time.saturating_sub(root) / 2).min(time / 2);
+        while low > 0 && beats(low - 1) {
+            low -= 1;
+        }
+        while !beats(low) {
+            low += 1;
+        }
+        (time - 2 * low + 1) as usize
+    }
+
+    /// Every charge time that beats the record under `physics`.
+    ///
+    /// Distance is speed times moving time. Speed never falls and moving time never
+    /// rises as charge time grows, and within each of the capped and uncapped
+    /// stretches distance first rises and then falls, so the winners are one
+    /// interval around the best charge time. Binary searches find the best charge
+    /// time and both ends without trying every charge time.
+    pub fn winning_charges(&self, physics: &Physics) -> IntervalSet<usize> {
+        let distance = |c: usize| physics.distance(self.time, c);
+        let record = self.record as u128;
+        let best = first_true(0, self.time, |c| distance(c + 1) <= distance(c));
+        if distance(best) <= record {
+            return IntervalSet::new();
+        }
+        let start = first_true(0, best, |c| distance(c) > record);
+        let end = first_true(best, self.time + 1, |c| distance(c) <= record);
+        IntervalSet::from(Interval::new(start, end))
+    }
+
     pub fn extend_with_race(&self, other: &Race) -> Self {
         // concatinate the digits of the two numbers
         if self.time == 0 {
             return other.clone();
         }
-        let new_time = format!("{}{}", self.time, other.time).parse::<usize>().unwrap();
-        let new_record = format!("{}{}", self.record, other.record).parse::<usize>().unwrap();
+        let new_time = format!("{}{}", self.time, other.time)
+            .parse::<usize>()
+            .unwrap();
+        let new_record = format!("{}{}", self.record, other.record)
+            .parse::<usize>()
+            .unwrap();
         Self {
             time: new_time,
             record: new_record,
@@ -47,7 +139,7 @@ impl Race {
 }
 
 //
-fn parse_input(input: &str) -> Vec<Race> {
+pub fn parse_input(input: &str) -> Vec<Race> {
     let first_line = input.lines().next().unwrap();
     let second_line = input.lines().nth(1).unwrap();
     let times = first_line
@@ -80,14 +172,38 @@ pub fn solve6(input: &str) -> usize {
 
 pub fn solve6_star2(input: &str) -> usize {
     let races = parse_input(&input);
-    let race: Race = races.iter().fold(Race::new(0,0), |a, e| a.extend_with_race(e));
+    let race: Race = races
+        .iter()
+        .fold(Race::new(0, 0), |a, e| a.extend_with_race(e));
     race.num_ways_to_beat_record()
 }
 
+pub fn solve6_closed_form(input: &str) -> usize {
+    let races = parse_input(input);
+    races.iter().map(|r| r.num_ways_closed_form()).product()
+}
+
+pub fn solve6_star2_closed_form(input: &str) -> usize {
+    let races = parse_input(input);
+    let race = races
+        .iter()
+        .fold(Race::new(0, 0), |a, e| a.extend_with_race(e));
+    race.num_ways_closed_form()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn num_ways_counts_every_charge_time() {
+        // Charging for time - 1 still moves 1 mm, which beats a record of 0.
+        assert_eq!(Race::new(2, 0).num_ways_to_beat_record(), 1);
+        assert_eq!(Race::new(7, 5).num_ways_to_beat_record(), 6);
+        // A race with no time has no charge times rather than underflowing.
+        assert_eq!(Race::new(0, 0).num_ways_to_beat_record(), 0);
+    }
+
     #[test]
     fn parse_input6() {
         let input = std::fs::read_to_string("data/test_input6.txt").unwrap();
@@ -100,6 +216,61 @@ mod tests {
         let input = std::fs::read_to_string("data/test_input6.txt").unwrap();
         let result = solve6(&input);
         assert_eq!(result, 288);
+        assert_eq!(solve6_closed_form(&input), 288);
+    }
+
+    #[test]
+    fn winning_charges_match_scan() {
+        let mut models = vec![Physics::default()];
+        for acceleration in 0..4 {
+            for max_speed in [None, Some(0), Some(1), Some(5), Some(12)] {
+                for start_delay in [0, 1, 3, 30] {
+                    models.push(Physics {
+                        acceleration,
+                        max_speed,
+                        start_delay,
+                    });
+                }
+            }
+        }
+        for physics in &models {
+            for time in 0..25 {
+                for record in (0..100).step_by(3) {
+                    let race = Race::new(time, record);
+                    let scan = (0..=time)
+                        .filter(|&c| physics.distance(time, c) > record as u128)
+                        .map(|c| Interval::new(c, c + 1))
+                        .collect::<IntervalSet<_>>();
+                    assert_eq!(
+                        race.winning_charges(physics),
+                        scan,
+                        "{:?} time {} record {}",
+                        physics,
+                        time,
+                        record
+                    );
+                }
+            }
+        }
+        let race = Race::new(7, 9);
+        assert_eq!(race.winning_charges(&Physics::default()).len(), 4);
+        let capped = Physics {
+            max_speed: Some(2),
+            ..Physics::default()
+        };
+        // Capped at 2 mm/ms, charging longer than 2 ms only loses moving time.
+        let winners = race.winning_charges(&capped);
+        assert_eq!(winners.intervals(), &[Interval::new(2, 3)]);
+    }
+
+    #[test]
+    fn closed_form_matches_scan() {
+        for time in 0..40 {
+            for record in 0..time * time / 4 + 2 {
+                let race = Race::new(time, record);
+                assert_eq!(race.num_ways_closed_form(), race.num_ways_to_beat_record());
+            }
+        }
     }
 
     #[test]
@@ -121,5 +292,6 @@ mod tests {
         let input = std::fs::read_to_string("data/input6.txt").unwrap();
         let result = solve6_star2(&input);
         assert_eq!(result, 40651271);
+        assert_eq!(solve6_star2_closed_form(&input), 40651271);
     }
 }
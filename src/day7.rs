@@ -1,13 +1,18 @@
+use crate::stream;
 use core::fmt;
+use regex::Regex;
+use serde::Serialize;
+use std::io::{self, BufRead};
+use std::sync::OnceLock;
 use std::{cmp::Ordering, fmt::Formatter};
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-struct Card {
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct Card {
     pub rank: u8,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-enum HandType {
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
@@ -30,23 +35,49 @@ impl Card {
         };
         Self { rank }
     }
+
+    /// Like `from_char`, but reads `J` as a jack, between ten and queen.
+    pub fn from_poker_char(c: char) -> Option<Card> {
+        let rank = match c {
+            '2'..='9' => c.to_digit(10).unwrap() as u8,
+            'T' => 10,
+            'J' => 11,
+            'Q' => 12,
+            'K' => 13,
+            'A' => 14,
+            _ => return None,
+        };
+        Some(Card { rank })
+    }
+
+    pub fn to_char(self) -> char {
+        match self.rank {
+            1 => 'J',
+            10 => 'T',
+            11 => 'J',
+            12 => 'Q',
+            13 => 'K',
+            14 => 'A',
+            r => (b'0' + r) as char,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
-struct Hand {
-    pub cards: Vec<Card>,
+pub struct Hand {
+    pub cards: [Card; 5],
     pub bid: usize,
     pub hand_type: HandType,
 }
 
 impl Hand {
     pub fn from_line(line: &str) -> Self {
-        let re = regex::Regex::new(r"([2-9TJQKA]{5}) (\d+)").unwrap();
+        // Compiled once, since inputs can hold millions of hands.
+        static LINE: OnceLock<Regex> = OnceLock::new();
+        let re = LINE.get_or_init(|| Regex::new(r"([2-9TJQKA]{5}) (\d+)").unwrap());
         let captures = re.captures(line).unwrap();
-        let cards = captures[1]
-            .chars()
-            .map(|c| Card::from_char(c))
-            .collect::<Vec<_>>();
+        let mut chars = captures[1].chars();
+        let cards = std::array::from_fn(|_| Card::from_char(chars.next().unwrap()));
         let bid = captures[2].parse::<usize>().unwrap();
         let hand_type: HandType = find_type(&cards);
         Hand {
@@ -55,9 +86,30 @@ impl Hand {
             hand_type,
         }
     }
+
+    /// Packs the hand into an integer that sorts like the hand: the type in the
+    /// high bits, then 4 bits per card rank in order.
+    pub fn key(&self) -> u32 {
+        self.cards.iter().fold(self.hand_type as u32, |key, card| {
+            key << 4 | card.rank as u32
+        })
+    }
+
+    /// The cards as they were written in the input.
+    pub fn cards_str(&self) -> String {
+        self.cards.iter().map(|c| c.to_char()).collect()
+    }
+
+    /// Compares type first and then card by card, the way `key` orders hands.
+    pub fn cmp_by_fields(&self, other: &Hand) -> Ordering {
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.cards.cmp(&other.cards))
+    }
 }
 
-fn find_type(cards: &Vec<Card>) -> HandType {
+/// Classifies a hand, reading rank 1 as a joker.
+pub fn find_type(cards: &[Card]) -> HandType {
     let mut ranks = [0; 15];
     for card in cards.iter() {
         ranks[card.rank as usize] += 1;
@@ -118,7 +170,7 @@ fn find_type(cards: &Vec<Card>) -> HandType {
         return HandType::FullHouse;
     } else if num_triples == 1 {
         return HandType::ThreeOfAKind;
-    } else if num_pairs == 2  {
+    } else if num_pairs == 2 {
         return HandType::TwoPair;
     } else if num_pairs == 1 {
         return HandType::OnePair;
@@ -128,7 +180,7 @@ fn find_type(cards: &Vec<Card>) -> HandType {
 
 impl PartialEq for Hand {
     fn eq(&self, other: &Self) -> bool {
-        return self.cards == other.cards;
+        self.key() == other.key()
     }
 }
 
@@ -136,46 +188,140 @@ impl Eq for Hand {}
 
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.hand_type != other.hand_type {
-            return Some(self.hand_type.cmp(&other.hand_type));
-        }
-        for i in 0..5 {
-            if self.cards[i] == other.cards[i] {
-                continue;
-            } else {
-                return Some(self.cards[i].cmp(&other.cards[i]));
-            }
-        }
-        Some(Ordering::Equal)
+        Some(self.cmp(other))
     }
 }
 
 impl fmt::Display for Hand {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let repr = self
-            .cards
-            .iter()
-            .map(|c| match c.rank {
-                10 => "T".to_string(),
-                1 => "J".to_string(),
-                12 => "Q".to_string(),
-                13 => "K".to_string(),
-                14 => "A".to_string(),
-                _ => c.rank.to_string(),
-            })
-            .collect::<Vec<_>>()
-            .join("");
-        write!(f, "{}", format!("[{} {:?}]", repr, self.hand_type))
+        write!(f, "[{} {:?}]", self.cards_str(), self.hand_type)
     }
 }
 
 impl Ord for Hand {
     fn cmp(&self, other: &Hand) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        self.key().cmp(&other.key())
+    }
+}
+
+/// The rank jokers stand in for when the hand's type is found, or `None` if it
+/// has no jokers.
+///
+/// Jokers join the most common other rank. Equally common ranks all give the
+/// same type, so the highest is reported; five jokers count as aces.
+pub fn joker_substitution(cards: &[Card]) -> Option<Card> {
+    if !cards.iter().any(|c| c.rank == 1) {
+        return None;
+    }
+    let count = |card: &Card| cards.iter().filter(|c| *c == card).count();
+    let best = cards
+        .iter()
+        .filter(|c| c.rank != 1)
+        .max_by_key(|c| (count(c), c.rank))
+        .copied();
+    Some(best.unwrap_or(Card { rank: 14 }))
+}
+
+/// One line of the ranking table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RankedHand {
+    /// 1 for the weakest hand.
+    pub rank: usize,
+    pub hand: String,
+    pub hand_type: String,
+    /// The card jokers stood in for, if the hand has any.
+    pub joker: Option<char>,
+    pub bid: usize,
+    pub winnings: usize,
+}
+
+/// Every hand in rank order, with the ranks of hands that compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Ranking {
+    pub hands: Vec<RankedHand>,
+    /// Groups of ranks whose hands are equal. The puzzle does not say how to rank
+    /// them, so they keep their input order, as in `solve_star2`.
+    pub ties: Vec<Vec<usize>>,
+}
+
+impl Ranking {
+    pub fn new(mut hands: Vec<Hand>) -> Ranking {
+        hands.sort_by_key(Hand::key);
+        let ranked = hands
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| RankedHand {
+                rank: i + 1,
+                hand: hand.cards_str(),
+                hand_type: format!("{:?}", hand.hand_type),
+                joker: joker_substitution(&hand.cards).map(Card::to_char),
+                bid: hand.bid,
+                winnings: (i + 1) * hand.bid,
+            })
+            .collect();
+        let mut ties = Vec::new();
+        let mut start = 0;
+        for group in hands.chunk_by(|a, b| a.key() == b.key()) {
+            if group.len() > 1 {
+                ties.push((start + 1..=start + group.len()).collect());
+            }
+            start += group.len();
+        }
+        Ranking {
+            hands: ranked,
+            ties,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.hands.iter().map(|h| h.winnings).sum()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = "rank,hand,type,joker,bid,winnings\n".to_string();
+        for h in &self.hands {
+            let joker = h.joker.map(String::from).unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                h.rank, h.hand, h.hand_type, joker, h.bid, h.winnings
+            ));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
     }
 }
 
-fn parse_input(input: &str) -> Vec<Hand> {
+impl fmt::Display for Ranking {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>5}  {:<5}  {:<12}  {:<5}  {:>5}  {:>9}",
+            "rank", "hand", "type", "joker", "bid", "winnings"
+        )?;
+        for h in &self.hands {
+            writeln!(
+                f,
+                "{:>5}  {:<5}  {:<12}  {:<5}  {:>5}  {:>9}",
+                h.rank,
+                h.hand,
+                h.hand_type,
+                h.joker.map(String::from).unwrap_or_default(),
+                h.bid,
+                h.winnings
+            )?;
+        }
+        for group in &self.ties {
+            let ranks = group.iter().map(ToString::to_string).collect::<Vec<_>>();
+            writeln!(f, "tie: ranks {} hold equal hands", ranks.join(", "))?;
+        }
+        write!(f, "total winnings: {}", self.total())
+    }
+}
+
+pub fn parse_input(input: &str) -> Vec<Hand> {
     let mut hands = Vec::new();
     for line in input.lines() {
         hands.push(Hand::from_line(line));
@@ -183,15 +329,33 @@ fn parse_input(input: &str) -> Vec<Hand> {
     hands
 }
 
+pub fn solve_star2(input: &str) -> usize {
+    let mut hands = parse_input(input);
+    hands.sort_by_key(Hand::key);
+    hands.iter().enumerate().map(|(i, c)| (i + 1) * c.bid).sum()
+}
+
+/// Like `solve_star2`, but reads one hand at a time from `reader`.
+///
+/// Ranking needs every hand before any can be scored, so memory still grows
+/// with the input, but only by a packed key and a bid per hand.
+pub fn solve_star2_reader(reader: impl BufRead) -> io::Result<usize> {
+    let mut keyed = Vec::new();
+    stream::for_each_line(reader, |line| {
+        let hand = Hand::from_line(line);
+        keyed.push((hand.key(), hand.bid));
+    })?;
+    keyed.sort_by_key(|&(key, _)| key);
+    Ok(keyed
+        .iter()
+        .enumerate()
+        .map(|(i, (_, bid))| (i + 1) * bid)
+        .sum())
+}
+
 pub fn main() {
     let input = std::fs::read_to_string("data/input7.txt").unwrap();
-    let mut hands = parse_input(&input);
-    hands.sort();
-    let result: usize = hands.iter().enumerate().map( |(i, c)| {
-        // println!("{} {}", i+1, c);
-        (i+1) * c.bid
-    }).sum();
-    println!("Day 7 Result: {}", result);
+    println!("{}", Ranking::new(parse_input(&input)));
 }
 
 #[cfg(test)]
@@ -213,4 +377,48 @@ mod tests {
         let hand2 = Hand::from_line("2QQQK 483");
         assert!(hand1 > hand2);
     }
+
+    #[test]
+    fn test_ranking() {
+        let input = std::fs::read_to_string("data/test_input7.txt").unwrap();
+        let ranking = Ranking::new(parse_input(&input));
+        assert_eq!(ranking.total(), solve_star2(&input));
+        assert_eq!(
+            solve_star2_reader(input.as_bytes()).unwrap(),
+            ranking.total()
+        );
+        assert!(ranking.ties.is_empty());
+        let best = ranking.hands.last().unwrap();
+        assert_eq!((best.hand.as_str(), best.joker), ("KTJJT", Some('T')));
+        assert_eq!(best.hand_type, "FourOfAKind");
+        assert_eq!(ranking.hands[0].joker, None);
+        let csv = ranking.to_csv();
+        assert_eq!(csv.lines().count(), ranking.hands.len() + 1);
+        let json: serde_json::Value = serde_json::from_str(&ranking.to_json()).unwrap();
+        assert_eq!(json["hands"][4]["winnings"], best.winnings);
+
+        let input = "AAKKQ 10\n23456 1\nAAKKQ 20\n";
+        let tied = Ranking::new(parse_input(input));
+        assert_eq!(tied.ties, vec![vec![2, 3]]);
+        // Equal hands keep their input order, so every entry point gives 1 + 20 + 60.
+        assert_eq!(tied.total(), 81);
+        assert_eq!(solve_star2(input), 81);
+        assert_eq!(solve_star2_reader(input.as_bytes()).unwrap(), 81);
+        assert_eq!(
+            joker_substitution(&Hand::from_line("JJJJJ 1").cards),
+            Some(Card { rank: 14 })
+        );
+    }
+
+    #[test]
+    fn test_key_sorts_like_fields() {
+        let mut rng = crate::gen::Rng::new(7);
+        let input = crate::gen::hands(&mut rng, &crate::gen::HandsConfig::default());
+        let hands = parse_input(&input);
+        for pair in hands.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            assert_eq!(a.key().cmp(&b.key()), a.cmp_by_fields(b), "{} {}", a, b);
+        }
+        assert_eq!(Hand::from_line("JJJJJ 1").key(), 6 << 20 | 0x11111);
+    }
 }
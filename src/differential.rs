@@ -0,0 +1,213 @@
+//! Differential testing: every variant of a day/part must give the same answer.
+//!
+//! Inputs come from [`gen`](crate::gen), and a disagreement is shrunk to a small input that
+//! still shows it before it is reported.
+
+use crate::gen::Rng;
+use crate::progress::NoProgress;
+use crate::registry::{self, Solution};
+use crate::runner::{self, Outcome};
+use core::fmt;
+use std::fmt::Formatter;
+
+/// An input on which the variants of one day/part did not all agree.
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+    pub day: u8,
+    pub part: u8,
+    /// Seed of the generated input the disagreement was first seen on.
+    pub seed: u64,
+    /// The shrunk input.
+    pub input: String,
+    pub outcomes: Vec<(&'static str, Outcome)>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Day {} part {} variants disagree (seed {}):",
+            self.day, self.part, self.seed
+        )?;
+        for (variant, outcome) in &self.outcomes {
+            writeln!(f, "  {}: {}", variant, outcome)?;
+        }
+        writeln!(f, "on input:")?;
+        write!(f, "{}", self.input)
+    }
+}
+
+/// Runs every variant on `input`, in registry order.
+pub fn outcomes(variants: &[&Solution], input: &str) -> Vec<(&'static str, Outcome)> {
+    variants
+        .iter()
+        .map(|s| (s.variant, runner::run(s, input, &mut NoProgress)))
+        .collect()
+}
+
+// Which variants solved and which gave up. A shrunk input has to fail the same
+// way, or a wrong answer could shrink into an unrelated parse panic.
+fn signature(outcomes: &[(&'static str, Outcome)]) -> Option<Vec<bool>> {
+    let answers = outcomes.iter().map(|(_, o)| o.answer()).collect::<Vec<_>>();
+    if answers.windows(2).all(|w| w[0] == w[1]) {
+        return None;
+    }
+    Some(answers.iter().map(Option::is_some).collect())
+}
+
+// Inputs the day's lint rejects are outside what the variants promise to agree on.
+fn valid(day: u8, input: &str) -> bool {
+    let Some(lint) = registry::day(day).and_then(|d| d.lint) else {
+        return true;
+    };
+    // The lint parses the input, which panics on inputs shrinking has broken.
+    let report = runner::catch_quietly(|| lint(input));
+    matches!(report, Ok(report) if report.problems.is_empty())
+}
+
+/// Feeds `cases` generated inputs of the given size to every variant of
+/// `day`/`part`, returning the number of inputs checked.
+///
+/// Days with a single variant or without a generator check nothing.
+pub fn check(day: u8, part: u8, cases: u64, seed: u64, size: f64) -> Result<u64, Disagreement> {
+    let Some(generate) = registry::day(day).and_then(|d| d.generate) else {
+        return Ok(0);
+    };
+    let inputs = (seed..seed + cases).map(|seed| (seed, generate(&mut Rng::new(seed), size)));
+    check_inputs(day, part, inputs)
+}
+
+/// Like [`check`], but for inputs from anywhere, each paired with the seed it
+/// was generated from.
+pub fn check_inputs(
+    day: u8,
+    part: u8,
+    inputs: impl IntoIterator<Item = (u64, String)>,
+) -> Result<u64, Disagreement> {
+    let variants = registry::variants(day, part);
+    if variants.len() < 2 {
+        return Ok(0);
+    }
+    let mut checked = 0;
+    for (seed, input) in inputs {
+        if let Some(failure) = signature(&outcomes(&variants, &input)) {
+            let input = shrink(&input, |candidate| {
+                valid(day, candidate)
+                    && signature(&outcomes(&variants, candidate)).as_ref() == Some(&failure)
+            });
+            return Err(Disagreement {
+                day,
+                part,
+                seed,
+                outcomes: outcomes(&variants, &input),
+                input,
+            });
+        }
+        checked += 1;
+    }
+    Ok(checked)
+}
+
+/// Greedily shrinks `input` while `fails` still holds, by dropping lines and
+/// making numbers smaller, until no single step helps.
+pub fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut current = input.to_string();
+    loop {
+        let next = drop_lines(&current)
+            .chain(smaller_numbers(&current))
+            .find(|candidate| fails(candidate));
+        match next {
+            Some(candidate) => current = candidate,
+            None => return current,
+        }
+    }
+}
+
+fn drop_lines(input: &str) -> impl Iterator<Item = String> + '_ {
+    let lines = input.lines().collect::<Vec<_>>();
+    (0..lines.len()).map(move |skip| {
+        lines
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != skip)
+            .map(|(_, line)| format!("{}\n", line))
+            .collect()
+    })
+}
+
+// Every number in the input replaced, one at a time, by 0, its half and its
+// predecessor, so big numbers shrink quickly and small ones still reach the edge.
+fn smaller_numbers(input: &str) -> impl Iterator<Item = String> + '_ {
+    let bytes = input.as_bytes();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i].is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            numbers.push((start, i));
+        } else {
+            i += 1;
+        }
+    }
+    numbers.into_iter().flat_map(move |(start, end)| {
+        let n = input[start..end].parse::<usize>().unwrap_or(0);
+        let mut smaller = vec![0, n / 2, n.saturating_sub(1)];
+        smaller.dedup();
+        smaller
+            .into_iter()
+            .filter(move |&m| m < n)
+            .map(move |m| format!("{}{}{}", &input[..start], m, &input[end..]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::gen::{self, AlmanacConfig};
+
+    #[test]
+    fn test_registered_variants_agree() {
+        // Sizes small enough for the brute-force variants.
+        for (day, size) in [(4, 0.1), (6, 0.05)] {
+            for part in 1..=2 {
+                if let Err(disagreement) = check(day, part, 30, 0, size) {
+                    panic!("{}", disagreement);
+                }
+            }
+        }
+        assert_eq!(check(4, 2, 3, 0, 0.1).unwrap(), 3);
+        assert_eq!(check(1, 1, 3, 0, 1.0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_day5_variants_agree() {
+        // Small dense almanacs, so seed ranges often end next to a low location.
+        let config = AlmanacConfig {
+            seed_ranges: 3,
+            ranges_per_map: 6,
+            max_value: 200,
+            max_seed_range: 30,
+            ..AlmanacConfig::default()
+        };
+        let inputs = (0..100).map(|seed| (seed, gen::almanac(&mut Rng::new(seed), &config)));
+        if let Err(disagreement) = check_inputs(5, 2, inputs) {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    fn test_shrink() {
+        // Fails whenever some number on some line is at least 10.
+        let fails = |input: &str| {
+            input
+                .split(|c: char| !c.is_ascii_digit())
+                .any(|n| n.parse::<usize>().is_ok_and(|n| n >= 10))
+        };
+        let input = "1 2 3\n400 5\n6 7000\n";
+        assert_eq!(shrink(input, fails), "0 10\n");
+    }
+}
@@ -0,0 +1,14 @@
+//! Why a solver returned without an answer.
+
+use crate::progress::Cancelled;
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+    /// The input parsed but cannot be solved, e.g. an almanac whose maps do not
+    /// chain from seed to location.
+    #[error("invalid input: {0}")]
+    Invalid(String),
+}
@@ -0,0 +1,590 @@
+//! Seedable generators for random puzzle inputs.
+//!
+//! Every generator takes an [`Rng`] and a config whose size fields default to
+//! something close to the real inputs, and returns text the day's parser accepts.
+
+use crate::registry;
+use std::collections::HashSet;
+
+const DIGIT_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const CATEGORIES: [&str; 6] = [
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+];
+const CARDS: &str = "23456789TJQKA";
+
+/// A small SplitMix64 generator, so inputs are reproducible from a seed alone.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `[0, n)`. `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `[low, high]`.
+    pub fn between(&mut self, low: usize, high: usize) -> usize {
+        low + self.below(high - low + 1)
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// `count` distinct numbers from `[low, high]`, in random order, or all of
+    /// them if there are fewer.
+    pub fn distinct(&mut self, count: usize, low: usize, high: usize) -> Vec<usize> {
+        let size = high - low + 1;
+        if count.saturating_mul(4) >= size {
+            let mut pool = (low..=high).collect::<Vec<_>>();
+            self.shuffle(&mut pool);
+            pool.truncate(count);
+            return pool;
+        }
+        // Sparse picks from a wide range: draw until enough distinct numbers came up.
+        let mut seen = HashSet::new();
+        let mut picked = Vec::new();
+        while picked.len() < count {
+            let n = self.between(low, high);
+            if seen.insert(n) {
+                picked.push(n);
+            }
+        }
+        picked
+    }
+}
+
+/// Day 1: lines of letters, digits and spelled-out digits.
+#[derive(Debug, Clone)]
+pub struct CalibrationConfig {
+    pub lines: usize,
+    pub max_tokens: usize,
+    /// Whether lines may contain digits spelled out as words.
+    pub words: bool,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        CalibrationConfig {
+            lines: 1000,
+            max_tokens: 8,
+            words: true,
+        }
+    }
+}
+
+pub fn calibration(rng: &mut Rng, config: &CalibrationConfig) -> String {
+    let mut out = String::new();
+    for _ in 0..config.lines {
+        let tokens = rng.between(1, config.max_tokens.max(1));
+        // Every line needs a real digit, or part 1 has nothing to read.
+        let digit_at = rng.below(tokens);
+        for t in 0..tokens {
+            match rng.below(3) {
+                _ if t == digit_at => out.push((b'1' + rng.below(9) as u8) as char),
+                0 if config.words => out.push_str(DIGIT_WORDS[rng.below(DIGIT_WORDS.len())]),
+                1 => out.push((b'1' + rng.below(9) as u8) as char),
+                _ => out.push((b'a' + rng.below(26) as u8) as char),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Day 2: games of cubes drawn from a bag.
+#[derive(Debug, Clone)]
+pub struct GamesConfig {
+    pub games: usize,
+    /// Cube colours to draw from. The solver only counts red, green and blue.
+    pub colours: Vec<String>,
+    pub max_sets: usize,
+    pub max_cubes: usize,
+}
+
+impl Default for GamesConfig {
+    fn default() -> Self {
+        GamesConfig {
+            games: 100,
+            colours: ["red", "green", "blue"].map(String::from).to_vec(),
+            max_sets: 6,
+            max_cubes: 20,
+        }
+    }
+}
+
+pub fn games(rng: &mut Rng, config: &GamesConfig) -> String {
+    let mut out = String::new();
+    for id in 1..=config.games {
+        let sets = (0..rng.between(1, config.max_sets.max(1)))
+            .map(|_| {
+                let count = rng.between(1, config.colours.len());
+                rng.distinct(count, 0, config.colours.len() - 1)
+                    .into_iter()
+                    .map(|c| {
+                        let cubes = rng.between(1, config.max_cubes.max(1));
+                        format!("{} {}", cubes, config.colours[c])
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>();
+        out.push_str(&format!("Game {}: {}\n", id, sets.join("; ")));
+    }
+    out
+}
+
+/// Day 3: an engine schematic of numbers and symbols.
+#[derive(Debug, Clone)]
+pub struct SchematicConfig {
+    pub width: usize,
+    pub height: usize,
+    /// Chance of starting a number at any free cell.
+    pub number_density: f64,
+    /// Chance of a symbol at any cell left empty.
+    pub symbol_density: f64,
+    pub symbols: String,
+}
+
+impl Default for SchematicConfig {
+    fn default() -> Self {
+        SchematicConfig {
+            width: 140,
+            height: 140,
+            number_density: 0.08,
+            symbol_density: 0.03,
+            symbols: "*#+$/=%@&-".to_string(),
+        }
+    }
+}
+
+pub fn schematic(rng: &mut Rng, config: &SchematicConfig) -> String {
+    let symbols = config.symbols.chars().collect::<Vec<_>>();
+    let mut grid = vec![vec!['.'; config.width]; config.height];
+    for row in &mut grid {
+        let mut j = 0;
+        while j < config.width {
+            if rng.chance(config.number_density) {
+                let number = rng.between(1, 999).to_string();
+                // Keep a gap after each number so neighbours never merge.
+                if j + number.len() < config.width {
+                    for (k, c) in number.chars().enumerate() {
+                        row[j + k] = c;
+                    }
+                    j += number.len() + 1;
+                    continue;
+                }
+            }
+            if !symbols.is_empty() && rng.chance(config.symbol_density) {
+                row[j] = *rng.choose(&symbols);
+            }
+            j += 1;
+        }
+    }
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Day 4: scratchcards with winning numbers and the numbers you have.
+#[derive(Debug, Clone)]
+pub struct DeckConfig {
+    pub cards: usize,
+    pub winning: usize,
+    pub yours: usize,
+    pub max_number: usize,
+    /// Average number of matches per card. Copies grow exponentially once this
+    /// passes 1, and part 2 overflows on long decks.
+    pub mean_matches: f64,
+}
+
+impl Default for DeckConfig {
+    fn default() -> Self {
+        DeckConfig {
+            cards: 200,
+            winning: 10,
+            yours: 25,
+            max_number: 99,
+            mean_matches: 0.8,
+        }
+    }
+}
+
+pub fn deck(rng: &mut Rng, config: &DeckConfig) -> String {
+    let width = config.max_number.to_string().len();
+    let id_width = config.cards.to_string().len();
+    let format = |numbers: &[usize]| {
+        numbers
+            .iter()
+            .map(|n| format!("{:>width$}", n, width = width))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut out = String::new();
+    for id in 1..=config.cards {
+        // Zero is not a valid number.
+        let numbers = rng.distinct(config.winning + config.yours, 1, config.max_number);
+        let (winning, rest) = numbers.split_at(config.winning.min(numbers.len()));
+        let max_matches = winning.len().min(config.yours);
+        let chance = config.mean_matches / winning.len().max(1) as f64;
+        let matches = (0..max_matches).filter(|_| rng.chance(chance)).count();
+        let mut yours = winning[..matches].to_vec();
+        yours.extend(rest.iter().take(config.yours - matches));
+        rng.shuffle(&mut yours);
+        out.push_str(&format!(
+            "Card {:>id_width$}: {} | {}\n",
+            id,
+            format(winning),
+            format(&yours),
+            id_width = id_width
+        ));
+    }
+    out
+}
+
+/// Day 5: seed ranges and a chain of maps from seed to location.
+#[derive(Debug, Clone)]
+pub struct AlmanacConfig {
+    pub seed_ranges: usize,
+    /// Number of maps between seed and location.
+    pub maps: usize,
+    pub ranges_per_map: usize,
+    /// Every map is a bijection on `[0, max_value)`.
+    pub max_value: usize,
+    pub max_seed_range: usize,
+}
+
+impl Default for AlmanacConfig {
+    fn default() -> Self {
+        AlmanacConfig {
+            seed_ranges: 10,
+            maps: 7,
+            ranges_per_map: 30,
+            max_value: 4_000_000_000,
+            max_seed_range: 500_000_000,
+        }
+    }
+}
+
+// 0 -> "a", 25 -> "z", 26 -> "ba": category names may only contain letters.
+fn letters(mut n: usize) -> String {
+    let mut name = vec![b'a' + (n % 26) as u8];
+    while n >= 26 {
+        n /= 26;
+        name.push(b'a' + (n % 26) as u8);
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+// seed, the puzzle's own categories, invented ones if more maps are asked for, location.
+fn categories(maps: usize) -> Vec<String> {
+    let mut names = vec!["seed".to_string()];
+    for i in 0..maps.saturating_sub(1) {
+        names.push(match CATEGORIES.get(i) {
+            Some(name) => name.to_string(),
+            None => format!("extra{}", letters(i - CATEGORIES.len())),
+        });
+    }
+    names.push("location".to_string());
+    names
+}
+
+pub fn almanac(rng: &mut Rng, config: &AlmanacConfig) -> String {
+    let max_value = config.max_value.max(2);
+    let seeds = (0..config.seed_ranges)
+        .map(|_| {
+            let start = rng.between(1, max_value - 1);
+            let length = rng.between(1, config.max_seed_range.min(max_value - start).max(1));
+            format!("{} {}", start, length)
+        })
+        .collect::<Vec<_>>();
+    let mut out = format!("seeds: {}\n", seeds.join(" "));
+    let names = categories(config.maps.max(1));
+    for pair in names.windows(2) {
+        out.push_str(&format!("\n{}-to-{} map:\n", pair[0], pair[1]));
+        // Cut [0, max_value) into pieces, then lay the same lengths out in a new order.
+        let pieces = config.ranges_per_map.clamp(1, max_value);
+        let mut cuts = rng.distinct(pieces - 1, 1, max_value - 1);
+        cuts.push(0);
+        cuts.push(max_value);
+        cuts.sort();
+        let lengths = cuts.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+        let mut order = (0..lengths.len()).collect::<Vec<_>>();
+        rng.shuffle(&mut order);
+        let mut destination = 0;
+        let mut ranges = Vec::new();
+        for i in order {
+            ranges.push(format!("{} {} {}", destination, cuts[i], lengths[i]));
+            destination += lengths[i];
+        }
+        rng.shuffle(&mut ranges);
+        out.push_str(&ranges.join("\n"));
+        out.push('\n');
+    }
+    out
+}
+
+/// Day 6: race times and record distances.
+#[derive(Debug, Clone)]
+pub struct RaceConfig {
+    pub races: usize,
+    pub max_time: usize,
+}
+
+impl Default for RaceConfig {
+    fn default() -> Self {
+        RaceConfig {
+            races: 4,
+            max_time: 99,
+        }
+    }
+}
+
+pub fn race_sheet(rng: &mut Rng, config: &RaceConfig) -> String {
+    let mut times = Vec::new();
+    let mut records = Vec::new();
+    for _ in 0..config.races {
+        let time = rng.between(2, config.max_time.max(2));
+        // The record is what some charge time achieves, so the best ones beat it.
+        let charge = rng.between(0, time / 2);
+        times.push(time.to_string());
+        records.push((charge * (time - charge)).to_string());
+    }
+    let width = records.iter().map(String::len).max().unwrap_or(0);
+    let line = |name: &str, values: &[String]| {
+        let values = values
+            .iter()
+            .map(|v| format!("{:>width$}", v, width = width))
+            .collect::<Vec<_>>();
+        format!("{:<9} {}\n", name, values.join("   "))
+    };
+    line("Time:", &times) + &line("Distance:", &records)
+}
+
+/// Day 7: Camel Cards hands and bids.
+#[derive(Debug, Clone)]
+pub struct HandsConfig {
+    pub hands: usize,
+    pub max_bid: usize,
+    /// Whether hands may contain `J`, which part 2 reads as a joker.
+    pub jokers: bool,
+}
+
+impl Default for HandsConfig {
+    fn default() -> Self {
+        HandsConfig {
+            hands: 1000,
+            max_bid: 1000,
+            jokers: true,
+        }
+    }
+}
+
+pub fn hands(rng: &mut Rng, config: &HandsConfig) -> String {
+    let cards = CARDS
+        .chars()
+        .filter(|&c| config.jokers || c != 'J')
+        .collect::<Vec<_>>();
+    let mut out = String::new();
+    for _ in 0..config.hands {
+        let hand = (0..5).map(|_| *rng.choose(&cards)).collect::<String>();
+        out.push_str(&format!("{} {}\n", hand, rng.between(1, config.max_bid)));
+    }
+    out
+}
+
+// `n` scaled by `size`, but never below 1.
+fn scale(n: usize, size: f64) -> usize {
+    ((n as f64 * size).round() as usize).max(1)
+}
+
+/// A calibration document from the default config, with `lines` scaled by `size`.
+pub fn sized_calibration(rng: &mut Rng, size: f64) -> String {
+    let defaults = CalibrationConfig::default();
+    let config = CalibrationConfig {
+        lines: scale(defaults.lines, size),
+        ..defaults
+    };
+    calibration(rng, &config)
+}
+
+/// A game record from the default config, with `games` scaled by `size`.
+pub fn sized_games(rng: &mut Rng, size: f64) -> String {
+    let defaults = GamesConfig::default();
+    let config = GamesConfig {
+        games: scale(defaults.games, size),
+        ..defaults
+    };
+    games(rng, &config)
+}
+
+/// A schematic from the default config, with its area scaled by `size`.
+pub fn sized_schematic(rng: &mut Rng, size: f64) -> String {
+    let defaults = SchematicConfig::default();
+    let side = (defaults.height as f64 * size.sqrt()).round().max(1.0) as usize;
+    let config = SchematicConfig {
+        width: side,
+        height: side,
+        ..defaults
+    };
+    schematic(rng, &config)
+}
+
+/// A scratchcard deck from the default config, with `cards` scaled by `size`.
+pub fn sized_deck(rng: &mut Rng, size: f64) -> String {
+    let defaults = DeckConfig::default();
+    let config = DeckConfig {
+        cards: scale(defaults.cards, size),
+        ..defaults
+    };
+    deck(rng, &config)
+}
+
+/// A almanac from the default config, with `max_seed_range` scaled by `size`.
+pub fn sized_almanac(rng: &mut Rng, size: f64) -> String {
+    let defaults = AlmanacConfig::default();
+    let config = AlmanacConfig {
+        max_seed_range: scale(defaults.max_seed_range, size),
+        ..defaults
+    };
+    almanac(rng, &config)
+}
+
+/// A race sheet from the default config, with `max_time` scaled by `size`.
+pub fn sized_race_sheet(rng: &mut Rng, size: f64) -> String {
+    let defaults = RaceConfig::default();
+    let config = RaceConfig {
+        max_time: scale(defaults.max_time, size),
+        ..defaults
+    };
+    race_sheet(rng, &config)
+}
+
+/// A list of hands from the default config, with `hands` scaled by `size`.
+pub fn sized_hands(rng: &mut Rng, size: f64) -> String {
+    let defaults = HandsConfig::default();
+    let config = HandsConfig {
+        hands: scale(defaults.hands, size),
+        ..defaults
+    };
+    hands(rng, &config)
+}
+
+/// An input for `day` from the default config scaled by `size`, where 1 is about
+/// the size of a real input. `None` for days without a generator.
+pub fn generate(day: u8, seed: u64, size: f64) -> Option<String> {
+    let generate = registry::day(day)?.generate?;
+    Some(generate(&mut Rng::new(seed), size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+    use crate::{day1, day2, day3, day4, day5, day6, day7};
+
+    #[test]
+    fn test_same_seed_same_input() {
+        for day in 1..=7 {
+            assert_eq!(generate(day, 7, 0.05), generate(day, 7, 0.05));
+            assert_ne!(generate(day, 7, 0.05), generate(day, 8, 0.05));
+        }
+        assert_eq!(generate(8, 7, 1.0), None);
+    }
+
+    // A few small inputs per day, since the day 2 and day 4 parsers compile a
+    // regex per line; the differential tests run bigger generated inputs.
+    #[test]
+    fn test_parsers_accept_generated_inputs() {
+        for seed in 0..3 {
+            let mut rng = Rng::new(seed);
+            let input = calibration(&mut rng, &CalibrationConfig::default());
+            day1::find_solution(&input, false);
+            day1::find_solution(&input, true);
+
+            let config = GamesConfig {
+                games: 20,
+                colours: ["red", "green", "blue", "teal"].map(String::from).to_vec(),
+                ..GamesConfig::default()
+            };
+            let input = games(&mut rng, &config);
+            assert_eq!(day2::parse_input(&input).len(), 20);
+
+            let config = SchematicConfig {
+                width: 30,
+                height: 20,
+                ..SchematicConfig::default()
+            };
+            let input = schematic(&mut rng, &config);
+            assert_eq!(day3::parse_input(&input).height(), 20);
+
+            let config = DeckConfig {
+                cards: 30,
+                ..DeckConfig::default()
+            };
+            let input = deck(&mut rng, &config);
+            assert_eq!(day4::parse_input(&input).len(), 30);
+            day4::solve_star2(&input);
+
+            let config = AlmanacConfig {
+                maps: 9,
+                ranges_per_map: 5,
+                max_value: 1000,
+                max_seed_range: 20,
+                ..AlmanacConfig::default()
+            };
+            let input = almanac(&mut rng, &config);
+            let (seeds, maps) = day5::parse_input(&input);
+            assert_eq!(seeds.seeds.len(), 20);
+            assert_eq!(maps.len(), 9);
+            assert!(day5::validate(&maps).is_empty());
+            assert!(maps.iter().all(|m| m.coverage().bijective));
+            day5::solve(&input, &mut NoProgress).unwrap();
+
+            let input = race_sheet(&mut rng, &RaceConfig::default());
+            assert_eq!(day6::parse_input(&input).len(), 4);
+            day6::solve6(&input);
+
+            let config = HandsConfig {
+                hands: 100,
+                jokers: false,
+                ..HandsConfig::default()
+            };
+            let input = hands(&mut rng, &config);
+            assert!(!input.contains('J'));
+            assert_eq!(day7::parse_input(&input).len(), 100);
+        }
+    }
+}
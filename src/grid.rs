@@ -0,0 +1,225 @@
+//! Flood fill and connected components on a rectangular grid.
+//!
+//! Cells are `(row, column)` pairs, and which cells belong to a region is decided
+//! by a predicate, so the grid itself can be stored any way the caller likes.
+
+use crate::interval::Interval;
+
+pub type Cell = (usize, usize);
+
+/// Which neighbouring cells are connected to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, down, left and right.
+    Four,
+    /// Like `Four`, plus the diagonals.
+    Eight,
+}
+
+impl Connectivity {
+    pub fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// The neighbours of `cell` that lie inside a grid of `(height, width)`.
+pub fn neighbours(
+    (height, width): (usize, usize),
+    (i, j): Cell,
+    connectivity: Connectivity,
+) -> impl Iterator<Item = Cell> {
+    connectivity.offsets().iter().filter_map(move |&(di, dj)| {
+        let i = i.checked_add_signed(di).filter(|&i| i < height)?;
+        let j = j.checked_add_signed(dj).filter(|&j| j < width)?;
+        Some((i, j))
+    })
+}
+
+/// A connected set of cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub label: usize,
+    /// Cells in row-major order.
+    pub cells: Vec<Cell>,
+    /// The rows and columns the region spans.
+    pub bounds: (Interval<usize>, Interval<usize>),
+    /// Number of cell sides that do not face another cell of the region,
+    /// whatever the connectivity.
+    pub perimeter: usize,
+}
+
+impl Region {
+    fn new(label: usize, mut cells: Vec<Cell>) -> Region {
+        cells.sort();
+        let rows = Interval::new(cells[0].0, cells[cells.len() - 1].0 + 1);
+        let left = cells.iter().map(|c| c.1).min().unwrap();
+        let right = cells.iter().map(|c| c.1).max().unwrap();
+        // Every shared side is counted once from each of its two cells.
+        let shared = cells
+            .iter()
+            .flat_map(|&(i, j)| [(i + 1, j), (i, j + 1)])
+            .filter(|c| cells.binary_search(c).is_ok())
+            .count();
+        Region {
+            label,
+            perimeter: 4 * cells.len() - 2 * shared,
+            bounds: (rows, Interval::new(left, right + 1)),
+            cells,
+        }
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The first cell in row-major order.
+    pub fn first(&self) -> Cell {
+        self.cells[0]
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.cells.binary_search(&cell).is_ok()
+    }
+}
+
+/// Every region of a grid, with each cell's region label.
+#[derive(Debug, Clone)]
+pub struct Components {
+    labels: Vec<Vec<Option<usize>>>,
+    /// Regions in the row-major order of their first cells; a region's label is
+    /// its index.
+    pub regions: Vec<Region>,
+}
+
+impl Components {
+    pub fn label(&self, (i, j): Cell) -> Option<usize> {
+        *self.labels.get(i)?.get(j)?
+    }
+
+    pub fn region_at(&self, cell: Cell) -> Option<&Region> {
+        self.label(cell).map(|label| &self.regions[label])
+    }
+}
+
+fn fill(
+    size: (usize, usize),
+    start: Cell,
+    connectivity: Connectivity,
+    include: &impl Fn(Cell) -> bool,
+    mut claim: impl FnMut(Cell) -> bool,
+) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut stack = vec![start];
+    claim(start);
+    while let Some(cell) = stack.pop() {
+        cells.push(cell);
+        for next in neighbours(size, cell, connectivity) {
+            if include(next) && claim(next) {
+                stack.push(next);
+            }
+        }
+    }
+    cells
+}
+
+/// The region of cells connected to `start` for which `include` holds, or `None`
+/// if it does not hold for `start` itself.
+pub fn flood_fill(
+    size: (usize, usize),
+    start: Cell,
+    connectivity: Connectivity,
+    include: impl Fn(Cell) -> bool,
+) -> Option<Region> {
+    if start.0 >= size.0 || start.1 >= size.1 || !include(start) {
+        return None;
+    }
+    let mut seen = vec![vec![false; size.1]; size.0];
+    let cells = fill(size, start, connectivity, &include, |(i, j)| {
+        !std::mem::replace(&mut seen[i][j], true)
+    });
+    Some(Region::new(0, cells))
+}
+
+/// Labels every maximal connected region of cells for which `include` holds.
+pub fn components(
+    size: (usize, usize),
+    connectivity: Connectivity,
+    include: impl Fn(Cell) -> bool,
+) -> Components {
+    let mut labels = vec![vec![None; size.1]; size.0];
+    let mut regions = Vec::new();
+    for i in 0..size.0 {
+        for j in 0..size.1 {
+            if labels[i][j].is_some() || !include((i, j)) {
+                continue;
+            }
+            let label = regions.len();
+            let cells = fill(size, (i, j), connectivity, &include, |(i, j)| {
+                labels[i][j].replace(label).is_none()
+            });
+            regions.push(Region::new(label, cells));
+        }
+    }
+    Components { labels, regions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PICTURE: &str = "##..#\n#..#.\n..#..\n.##.#\n";
+
+    fn picture() -> (Vec<Vec<char>>, (usize, usize)) {
+        let grid = PICTURE
+            .lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        let size = (grid.len(), grid[0].len());
+        (grid, size)
+    }
+
+    #[test]
+    fn test_components() {
+        let (grid, size) = picture();
+        let filled = |(i, j): Cell| grid[i][j] == '#';
+
+        let four = components(size, Connectivity::Four, filled);
+        let areas = four.regions.iter().map(Region::area).collect::<Vec<_>>();
+        assert_eq!(areas, vec![3, 1, 1, 3, 1]);
+        assert_eq!(four.label((3, 1)), Some(3));
+        assert_eq!(four.label((0, 2)), None);
+        let corner = &four.regions[0];
+        assert_eq!(corner.bounds, (Interval::new(0, 2), Interval::new(0, 2)));
+        assert_eq!(corner.perimeter, 8);
+        assert_eq!(four.regions[3].perimeter, 8);
+
+        let eight = components(size, Connectivity::Eight, filled);
+        let areas = eight.regions.iter().map(Region::area).collect::<Vec<_>>();
+        assert_eq!(areas, vec![3, 5, 1]);
+        assert_eq!(eight.region_at((3, 2)), eight.region_at((0, 4)));
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let (grid, size) = picture();
+        let empty = |(i, j): Cell| grid[i][j] == '.';
+        let region = flood_fill(size, (0, 2), Connectivity::Four, empty).unwrap();
+        assert_eq!(region.area(), 7);
+        assert!(region.contains((3, 0)));
+        assert!(!region.contains((3, 3)));
+        assert_eq!(flood_fill(size, (0, 0), Connectivity::Four, empty), None);
+        assert_eq!(flood_fill(size, (9, 0), Connectivity::Four, empty), None);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::registry::DEFAULT_VARIANT;
+use crate::runner::Outcome;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_PATH: &str = ".advent/history.jsonl";
+
+// Number of most recent runs shown in a timing sparkline.
+const SPARKLINE_RUNS: usize = 20;
+
+/// One line of the run history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    /// The solver variant that ran. History written before variants were
+    /// recorded only holds default runs.
+    #[serde(default = "default_variant")]
+    pub variant: String,
+    pub input_hash: String,
+    pub answer: Option<usize>,
+    pub status: String,
+    pub duration_ns: Option<u64>,
+    pub commit: Option<String>,
+    pub timestamp: u64,
+    /// The answer came from the answer cache, so `duration_ns` is the time of the
+    /// run that first computed it.
+    #[serde(default)]
+    pub cached: bool,
+}
+
+fn default_variant() -> String {
+    DEFAULT_VARIANT.to_string()
+}
+
+impl Record {
+    pub fn new(
+        day: u8,
+        part: u8,
+        variant: &str,
+        input_hash: &str,
+        outcome: &Outcome,
+        commit: Option<String>,
+        cached: bool,
+    ) -> Self {
+        let (status, duration_ns) = match outcome {
+            Outcome::Solved { elapsed, .. } => ("solved", Some(elapsed.as_nanos() as u64)),
+            Outcome::Timeout(_) => ("timeout", None),
+            Outcome::OutOfMemory => ("out of memory", None),
+            Outcome::Panic(_) => ("panic", None),
+            Outcome::Cancelled => ("cancelled", None),
+            Outcome::Failed(_) => ("failed", None),
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Record {
+            day,
+            part,
+            variant: variant.to_string(),
+            input_hash: input_hash.to_string(),
+            answer: outcome.answer(),
+            status: status.to_string(),
+            duration_ns,
+            commit,
+            timestamp,
+            cached,
+        }
+    }
+}
+
+/// FNV-1a hash of the input, stable across platforms and compiler versions.
+pub fn input_hash(input: &[u8]) -> String {
+    let hash = input.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+pub fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn append(path: &Path, records: &[Record]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for record in records {
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+    }
+    Ok(())
+}
+
+/// Reads the history, skipping with a warning any line that does not parse, so
+/// one corrupt record does not hide the rest.
+pub fn load(path: &Path) -> io::Result<Vec<Record>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut records = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => eprintln!("skipping line {} of {}: {}", i + 1, path.display(), e),
+        }
+    }
+    Ok(records)
+}
+
+/// An answer that differs from the previous answer of the same variant for the
+/// same input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    pub input_hash: String,
+    pub before: usize,
+    pub after: usize,
+    pub commit: Option<String>,
+}
+
+pub fn regressions(records: &[Record]) -> Vec<Regression> {
+    let mut last_answer: BTreeMap<(u8, u8, &str, &str), usize> = BTreeMap::new();
+    let mut regressions = Vec::new();
+    for record in records {
+        let Some(answer) = record.answer else {
+            continue;
+        };
+        let key = (
+            record.day,
+            record.part,
+            record.variant.as_str(),
+            record.input_hash.as_str(),
+        );
+        if let Some(before) = last_answer.insert(key, answer) {
+            if before != answer {
+                regressions.push(Regression {
+                    day: record.day,
+                    part: record.part,
+                    variant: record.variant.clone(),
+                    input_hash: record.input_hash.clone(),
+                    before,
+                    after: answer,
+                    commit: record.commit.clone(),
+                });
+            }
+        }
+    }
+    regressions
+}
+
+pub fn sparkline(values: &[u64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    values
+        .iter()
+        .map(|&v| {
+            if max == min {
+                return BARS[0];
+            }
+            let index = (v - min) as u128 * (BARS.len() as u128 - 1) / (max - min) as u128;
+            BARS[index as usize]
+        })
+        .collect()
+}
+
+/// Per day/part/variant trend summary followed by any answer regressions.
+pub fn report(records: &[Record]) -> String {
+    let mut by_part: BTreeMap<(u8, u8, &str), Vec<&Record>> = BTreeMap::new();
+    for record in records {
+        by_part
+            .entry((record.day, record.part, record.variant.as_str()))
+            .or_default()
+            .push(record);
+    }
+    let regressions = regressions(records);
+
+    let mut out = String::new();
+    for ((day, part, variant), runs) in &by_part {
+        // Cached runs repeat an earlier run's time, so only fresh runs are timed.
+        let timings = runs
+            .iter()
+            .filter(|r| !r.cached)
+            .filter_map(|r| r.duration_ns)
+            .collect::<Vec<u64>>();
+        let cached = runs.iter().filter(|r| r.cached).count();
+        let recent = &timings[timings.len().saturating_sub(SPARKLINE_RUNS)..];
+        let latest = runs
+            .iter()
+            .rev()
+            .find_map(|r| r.answer)
+            .map_or("-".to_string(), |a| a.to_string());
+        let last_status = &runs[runs.len() - 1].status;
+        out.push_str(&format!("Day {} part {}", day, part));
+        if *variant != DEFAULT_VARIANT {
+            out.push_str(&format!(" ({})", variant));
+        }
+        out.push_str(&format!(": {} runs", runs.len()));
+        if cached > 0 {
+            out.push_str(&format!(" ({} cached)", cached));
+        }
+        out.push_str(&format!(", answer {}, last {}", latest, last_status));
+        if let (Some(&last), Some(&best)) = (timings.last(), timings.iter().min()) {
+            out.push_str(&format!(
+                ", time {:.2?} (best {:.2?}) {}",
+                Duration::from_nanos(last),
+                Duration::from_nanos(best),
+                sparkline(recent)
+            ));
+        }
+        out.push('\n');
+        for r in regressions
+            .iter()
+            .filter(|r| r.day == *day && r.part == *part && r.variant == *variant)
+        {
+            out.push_str(&format!(
+                "  ! answer changed for input {}: {} -> {} (commit {})\n",
+                r.input_hash,
+                r.before,
+                r.after,
+                r.commit.as_deref().unwrap_or("unknown")
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(answer: usize, input_hash: &str, duration_ns: u64) -> Record {
+        Record {
+            day: 5,
+            part: 1,
+            variant: DEFAULT_VARIANT.to_string(),
+            input_hash: input_hash.to_string(),
+            answer: Some(answer),
+            status: "solved".to_string(),
+            duration_ns: Some(duration_ns),
+            commit: Some("abc1234".to_string()),
+            timestamp: 0,
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn test_input_hash_is_stable() {
+        assert_eq!(input_hash(b""), "cbf29ce484222325");
+        assert_eq!(input_hash(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[1, 8, 4, 1]), "▁█▄▁");
+        assert_eq!(sparkline(&[3, 3]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_regressions_only_for_same_input() {
+        let records = vec![
+            record(35, "aaaa", 10),
+            record(35, "aaaa", 12),
+            record(46, "bbbb", 11),
+            record(36, "aaaa", 9),
+        ];
+        let found = regressions(&records);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].before, found[0].after), (35, 36));
+    }
+
+    #[test]
+    fn test_variants_are_kept_apart() {
+        let mut intervals = record(9, "aaaa", 1);
+        intervals.variant = "intervals".to_string();
+        let records = vec![record(35, "aaaa", 10), intervals, record(35, "aaaa", 12)];
+        assert_eq!(regressions(&records), vec![]);
+        let report = report(&records);
+        assert!(report.contains("Day 5 part 1: 2 runs, answer 35"));
+        assert!(report.contains("Day 5 part 1 (intervals): 1 runs, answer 9"));
+    }
+
+    #[test]
+    fn test_load_skips_corrupt_lines() {
+        let path = std::env::temp_dir().join(format!("history-{}.jsonl", std::process::id()));
+        append(&path, &[record(35, "aaaa", 10)]).unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "{\"day\":5,\n").unwrap();
+        append(&path, &[record(36, "aaaa", 10)]).unwrap();
+        let answers = load(&path)
+            .unwrap()
+            .iter()
+            .map(|r| r.answer)
+            .collect::<Vec<_>>();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(answers, vec![Some(35), Some(36)]);
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let r = record(35, "aaaa", 10);
+        let line = serde_json::to_string(&r).unwrap();
+        assert_eq!(serde_json::from_str::<Record>(&line).unwrap(), r);
+    }
+
+    #[test]
+    fn test_report_counts_cached_runs_without_timing_them() {
+        let mut hit = record(35, "aaaa", 10);
+        hit.cached = true;
+        let records = vec![record(35, "aaaa", 20), hit];
+        let report = report(&records);
+        assert!(report.starts_with("Day 5 part 1: 2 runs (1 cached), answer 35, last solved"));
+        assert!(report.contains("time 20.00ns (best 20.00ns)"));
+
+        // History written before cached runs were recorded still loads.
+        let line = r#"{"day":5,"part":1,"input_hash":"aaaa","answer":35,"status":"solved","duration_ns":20,"commit":null,"timestamp":0}"#;
+        let old = serde_json::from_str::<Record>(line).unwrap();
+        assert!(!old.cached);
+        assert_eq!(old.variant, DEFAULT_VARIANT);
+    }
+}
@@ -0,0 +1,315 @@
+//! Half-open integer intervals and sets of disjoint intervals, with the set
+//! operations and shifts that day 5's range maps and day 6's races need.
+//!
+//! Intervals are generic over any signed or unsigned integer type.
+
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Add, Sub};
+
+/// The integer types intervals can be built from.
+pub trait Integer: Copy + Ord + Debug + Default + Add<Output = Self> + Sub<Output = Self> {}
+
+impl<T: Copy + Ord + Debug + Default + Add<Output = T> + Sub<Output = T>> Integer for T {}
+
+/// The half-open interval `[start, end)`. It is empty when `end <= start`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn new(start: T, end: T) -> Interval<T> {
+        Interval { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::default()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        !self.intersect(other).is_empty()
+    }
+
+    /// The common part of both intervals, possibly empty.
+    pub fn intersect(&self, other: &Interval<T>) -> Interval<T> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The parts below and from `at`, either of which may be empty.
+    pub fn split_at(&self, at: T) -> (Interval<T>, Interval<T>) {
+        let at = at.clamp(self.start, self.end.max(self.start));
+        (
+            Interval::new(self.start, at),
+            Interval::new(at, self.end.max(at)),
+        )
+    }
+
+    /// Moves the interval so that `from` lands on `to`.
+    ///
+    /// Taking two points rather than a signed offset lets unsigned intervals move
+    /// down, which is what `day5::Range` does for most of its ranges.
+    pub fn shift(&self, from: T, to: T) -> Interval<T> {
+        if to >= from {
+            let offset = to - from;
+            Interval::new(self.start + offset, self.end + offset)
+        } else {
+            let offset = from - to;
+            Interval::new(self.start - offset, self.end - offset)
+        }
+    }
+}
+
+impl<T: Debug> Debug for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:?}, {:?})", self.start, self.end)
+    }
+}
+
+impl<T> From<(T, T)> for Interval<T> {
+    fn from((start, end): (T, T)) -> Interval<T> {
+        Interval { start, end }
+    }
+}
+
+impl<T> From<Interval<T>> for (T, T) {
+    fn from(interval: Interval<T>) -> (T, T) {
+        (interval.start, interval.end)
+    }
+}
+
+/// A set of integers stored as sorted, disjoint, non-adjacent, non-empty intervals.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    // Sorts the intervals and joins the ones that overlap or touch.
+    fn normalised(mut intervals: Vec<Interval<T>>) -> IntervalSet<T> {
+        intervals.retain(|i| !i.is_empty());
+        intervals.sort();
+        let mut merged: Vec<Interval<T>> = Vec::new();
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> + '_ {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::default(), |total, i| total + i.len())
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let after = self.intervals.partition_point(|i| i.start <= value);
+        after > 0 && self.intervals[after - 1].contains(value)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = IntervalSet::normalised(intervals);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let intervals = self.intervals.iter().chain(&other.intervals).copied();
+        IntervalSet::normalised(intervals.collect())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (self.intervals[a], other.intervals[b]);
+            intervals.push(x.intersect(&y));
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet::normalised(intervals)
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let mut cut = other.intervals.iter().peekable();
+        for &interval in &self.intervals {
+            let mut rest = interval;
+            while let Some(c) = cut.peek() {
+                if c.end <= rest.start {
+                    cut.next();
+                    continue;
+                }
+                if c.start >= rest.end {
+                    break;
+                }
+                intervals.push(Interval::new(rest.start, c.start));
+                rest = Interval::new(c.end, rest.end);
+                if rest.is_empty() {
+                    break;
+                }
+                cut.next();
+            }
+            intervals.push(rest);
+        }
+        IntervalSet::normalised(intervals)
+    }
+
+    /// Everything in `bound` that is not in the set.
+    pub fn complement(&self, bound: Interval<T>) -> IntervalSet<T> {
+        IntervalSet::from(bound).difference(self)
+    }
+
+    /// Moves every interval so that `from` lands on `to`, as `Interval::shift` does.
+    pub fn shift(&self, from: T, to: T) -> IntervalSet<T> {
+        IntervalSet {
+            intervals: self.intervals.iter().map(|i| i.shift(from, to)).collect(),
+        }
+    }
+
+    /// The parts of the set below and from `at`.
+    pub fn split_at(&self, at: T) -> (IntervalSet<T>, IntervalSet<T>) {
+        let (mut below, mut above) = (Vec::new(), Vec::new());
+        for interval in &self.intervals {
+            let (low, high) = interval.split_at(at);
+            below.push(low);
+            above.push(high);
+        }
+        (
+            IntervalSet::normalised(below),
+            IntervalSet::normalised(above),
+        )
+    }
+}
+
+impl<T: Debug> Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(&self.intervals).finish()
+    }
+}
+
+impl<T: Integer> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> IntervalSet<T> {
+        IntervalSet::normalised(vec![interval])
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> IntervalSet<T> {
+        IntervalSet::normalised(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+    use std::collections::BTreeSet;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals.iter().map(|&i| Interval::from(i)).collect()
+    }
+
+    fn points(set: &IntervalSet<i32>) -> BTreeSet<i32> {
+        set.iter().flat_map(|i| i.start..i.end).collect()
+    }
+
+    #[test]
+    fn test_normalised() {
+        let s = set(&[(5, 8), (0, 2), (2, 3), (7, 10), (4, 4)]);
+        assert_eq!(s.intervals(), &[Interval::new(0, 3), Interval::new(5, 10)]);
+        assert_eq!(s.len(), 8);
+        assert!(s.contains(2) && !s.contains(3) && s.contains(9) && !s.contains(10));
+    }
+
+    #[test]
+    fn test_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(0, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 28)]));
+        assert_eq!(
+            a.complement(Interval::new(-5, 35)),
+            set(&[(-5, 0), (10, 20), (30, 35)])
+        );
+        assert_eq!(
+            a.split_at(25),
+            (set(&[(0, 10), (20, 25)]), set(&[(25, 30)]))
+        );
+    }
+
+    #[test]
+    fn test_shift_unsigned() {
+        // Day 5's "50 98 2" moves [98, 100) down onto [50, 52).
+        let s: IntervalSet<usize> = IntervalSet::from(Interval::new(98, 100));
+        assert_eq!(s.shift(98, 50).intervals(), &[Interval::new(50, 52)]);
+        assert_eq!(s.shift(50, 52).intervals(), &[Interval::new(100, 102)]);
+    }
+
+    #[test]
+    fn test_matches_point_sets() {
+        // Seeded, so the cases are the same on every run.
+        let mut rng = Rng::new(7);
+        let mut next = |bound: usize| rng.below(bound) as i32;
+        for _ in 0..500 {
+            let mut random_set = || {
+                let intervals = (0..next(5))
+                    .map(|_| {
+                        let start = next(40);
+                        (start, start + next(10))
+                    })
+                    .collect::<Vec<_>>();
+                set(&intervals)
+            };
+            let (a, b) = (random_set(), random_set());
+            let (pa, pb) = (points(&a), points(&b));
+            assert_eq!(points(&a.union(&b)), &pa | &pb);
+            assert_eq!(points(&a.intersection(&b)), &pa & &pb);
+            assert_eq!(points(&a.difference(&b)), &pa - &pb);
+            let bound = (0..50).collect::<BTreeSet<_>>();
+            assert_eq!(points(&a.complement(Interval::new(0, 50))), &bound - &pa);
+        }
+    }
+}
@@ -1,3 +1,6 @@
+pub mod answers;
+pub mod batch;
+pub mod cache;
 pub mod day1;
 pub mod day2;
 pub mod day3;
@@ -5,3 +8,23 @@ pub mod day4;
 pub mod day5;
 pub mod day6;
 pub mod day7;
+pub mod differential;
+pub mod error;
+pub mod gen;
+pub mod grid;
+pub mod history;
+pub mod interval;
+pub mod lint;
+pub mod math;
+pub mod poker;
+pub mod progress;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod search;
+pub mod stream;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod verify;
+pub mod viz;
+pub mod watch;
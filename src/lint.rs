@@ -0,0 +1,11 @@
+//! What a day's lint hook reports. Kept apart from the registry so day modules
+//! can build reports without depending on it.
+
+/// Result of checking an input before solving it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintReport {
+    /// Things that make the answer unreliable.
+    pub problems: Vec<String>,
+    /// Informational statistics about the input.
+    pub notes: Vec<String>,
+}
@@ -1,4 +1,578 @@
+use advent::answers::{self, Answers};
+use advent::batch;
+use advent::cache::{self, Cache};
+use advent::day5;
+use advent::day7;
+use advent::gen;
+use advent::history::{self, Record};
+use advent::progress::{NoProgress, ProgressBar};
+use advent::registry::{self, InputKind, Solution};
+use advent::report;
+use advent::runner::{self, Limits, Outcome};
+use advent::stream;
+use advent::verify;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const USAGE: &str =
+    "usage: advent run [--day N] [--part P] [--timeout SECS] [--memory MB] [--in-process]
+                  [--visualize] [--svg PATH] [--input PATH|-] [--inputs-dir DIR] [--no-cache]
+       advent history
+       advent cache clear
+       advent report [--out PATH] [--timeout SECS] [--memory MB]
+       advent query --day 5 --seed N [--example]
+       advent lint --day N [--example]
+       advent gen --day N [--seed S] [--size X]
+       advent rank [--example] [--csv PATH] [--json PATH]
+       advent verify
+       advent tui
+       advent watch --day N [--timeout SECS] [--memory MB]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+// Handles the flags shared by every command that runs solvers.
+fn parse_limit_arg(arg: &str, value: &mut dyn FnMut() -> String, limits: &mut Limits) -> bool {
+    match arg {
+        "--timeout" => {
+            let secs: f64 = value().parse().unwrap_or_else(|_| usage());
+            limits.timeout = Duration::from_secs_f64(secs);
+        }
+        "--memory" => {
+            let mb: u64 = value().parse().unwrap_or_else(|_| usage());
+            limits.memory = Some(mb << 20);
+        }
+        _ => return false,
+    }
+    true
+}
+
+struct RunArgs {
+    day: Option<u8>,
+    part: Option<u8>,
+    limits: Limits,
+    /// Whether --timeout or --memory was given, rather than the defaults used.
+    limited: bool,
+    in_process: bool,
+    visualize: bool,
+    svg: Option<PathBuf>,
+    input: Option<PathBuf>,
+    inputs_dir: Option<PathBuf>,
+    no_cache: bool,
+}
+
+fn parse_run_args(args: &[String]) -> RunArgs {
+    let mut run_args = RunArgs {
+        day: None,
+        part: None,
+        limits: Limits::default(),
+        limited: false,
+        in_process: false,
+        visualize: false,
+        svg: None,
+        input: None,
+        inputs_dir: None,
+        no_cache: false,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--day" => run_args.day = Some(value().parse().unwrap_or_else(|_| usage())),
+            "--part" => run_args.part = Some(value().parse().unwrap_or_else(|_| usage())),
+            "--in-process" => run_args.in_process = true,
+            "--visualize" => run_args.visualize = true,
+            "--svg" => run_args.svg = Some(PathBuf::from(value())),
+            "--input" => run_args.input = Some(PathBuf::from(value())),
+            "--inputs-dir" => run_args.inputs_dir = Some(PathBuf::from(value())),
+            "--no-cache" => run_args.no_cache = true,
+            arg if parse_limit_arg(arg, &mut value, &mut run_args.limits) => {
+                run_args.limited = true
+            }
+            _ => usage(),
+        }
+    }
+    // One SVG file holds one picture, so the day has to be fixed.
+    if run_args.svg.is_some() && run_args.day.is_none() {
+        usage();
+    }
+    // An input file belongs to one day, and stdin can only be read once, in
+    // this process, where limits cannot be enforced, and not again to picture it.
+    let pictured = run_args.visualize || run_args.svg.is_some();
+    match run_args.input.as_deref() {
+        Some(path) if path == Path::new(stream::STDIN) && run_args.part.is_none() => usage(),
+        Some(path) if path == Path::new(stream::STDIN) && (run_args.limited || pictured) => usage(),
+        Some(_) if run_args.day.is_none() => usage(),
+        _ => (),
+    }
+    if run_args.inputs_dir.is_some() && (run_args.day.is_none() || run_args.input.is_some()) {
+        usage();
+    }
+    run_args
+}
+
+fn run(args: &[String]) {
+    let args = parse_run_args(args);
+    let solutions = registry::defaults()
+        .filter(|s| args.day.is_none_or(|day| s.day == day))
+        .filter(|s| args.part.is_none_or(|part| s.part == part))
+        .collect::<Vec<&Solution>>();
+    if solutions.is_empty() {
+        eprintln!("no registered solutions match");
+        std::process::exit(1);
+    }
+    if let Some(path) = args
+        .input
+        .as_deref()
+        .filter(|p| *p == Path::new(stream::STDIN))
+    {
+        let solution = solutions[0];
+        let outcome = run_streamed(solution, path);
+        println!("Day {} part {}: {}", solution.day, solution.part, outcome);
+        return;
+    }
+    let exe = std::env::current_exe().unwrap();
+    if let (Some(dir), Some(day)) = (&args.inputs_dir, args.day) {
+        run_batch(day, &solutions, dir, &exe, &args);
+        return;
+    }
+    let commit = history::git_commit();
+    let mut cache = open_cache();
+    let mut records = Vec::new();
+    let mut linted = None;
+    for solution in solutions {
+        // A file given with --input is streamed on days that can, unless limits
+        // were asked for, which only the isolated child can enforce.
+        let streamed = registry::streaming(solution.day, solution.part).is_some();
+        if let Some(path) = args.input.as_deref().filter(|_| streamed && !args.limited) {
+            let outcome = run_streamed(solution, path);
+            println!("Day {} part {}: {}", solution.day, solution.part, outcome);
+            continue;
+        }
+        let input_path = args.input.clone().unwrap_or_else(|| solution.input_path());
+        let input = match std::fs::read_to_string(&input_path) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("Day {} part {}: {}", solution.day, solution.part, e);
+                continue;
+            }
+        };
+        // Lint each day once, before its first part runs.
+        if linted != Some(solution.day) {
+            linted = Some(solution.day);
+            if let Some(lint) = registry::day(solution.day).and_then(|d| d.lint) {
+                // The lint parses the input in this process, so a malformed input
+                // must not take the runner down before the isolated solve.
+                let problems = runner::catch_quietly(|| lint(&input).problems)
+                    .unwrap_or_else(|e| vec![format!("lint panicked: {}", e)]);
+                for problem in problems {
+                    println!("Day {} lint: {}", solution.day, problem);
+                }
+            }
+        }
+        let input_hash = history::input_hash(input.as_bytes());
+        let key = cache::Key {
+            day: solution.day,
+            part: solution.part,
+            input_hash: input_hash.clone(),
+            variant: solution.variant.to_string(),
+            build: cache::BUILD_ID.to_string(),
+        };
+        // --no-cache only skips the lookup; the fresh answer still replaces the old one.
+        let cached = match &cache {
+            Some(cache) if !args.no_cache => cache.get(&key),
+            _ => None,
+        };
+        let hit = cached.is_some();
+        let outcome = cached.unwrap_or_else(|| {
+            let mut bar = ProgressBar::new();
+            let outcome = if args.in_process {
+                runner::run(solution, &input, &mut bar)
+            } else {
+                runner::run_isolated(&exe, solution, &input_path, &args.limits, &mut bar)
+            };
+            bar.finish();
+            if let Some(cache) = &mut cache {
+                if let Err(e) = cache.insert(key, &outcome) {
+                    eprintln!("could not write answer cache: {}", e);
+                }
+            }
+            outcome
+        });
+        let suffix = if hit { " [cached]" } else { "" };
+        println!(
+            "Day {} part {}: {}{}",
+            solution.day, solution.part, outcome, suffix
+        );
+        records.push(Record::new(
+            solution.day,
+            solution.part,
+            solution.variant,
+            &input_hash,
+            &outcome,
+            commit.clone(),
+            hit,
+        ));
+    }
+    if let Err(e) = history::append(Path::new(history::HISTORY_PATH), &records) {
+        eprintln!("could not write run history: {}", e);
+    }
+    if args.visualize || args.svg.is_some() {
+        visualize(&args);
+    }
+}
+
+// The answer cache, or none if it cannot be read, so runs go ahead uncached.
+fn open_cache() -> Option<Cache> {
+    match Cache::load(Path::new(cache::CACHE_PATH)) {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            eprintln!("answer cache disabled: {}", e);
+            None
+        }
+    }
+}
+
+fn cache_command(args: &[String]) {
+    if args != ["clear"] {
+        usage();
+    }
+    match cache::clear(Path::new(cache::CACHE_PATH)) {
+        Ok(true) => println!("Cleared {}", cache::CACHE_PATH),
+        Ok(false) => println!("no answer cache to clear"),
+        Err(e) => {
+            eprintln!("could not clear {}: {}", cache::CACHE_PATH, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_batch(day: u8, solutions: &[&Solution], dir: &Path, exe: &Path, args: &RunArgs) {
+    let batch = batch::run(day, solutions, dir, |solution, input| {
+        eprintln!("Day {} part {}: {}...", day, solution.part, input.display());
+        if !args.in_process {
+            return runner::run_isolated(exe, solution, input, &args.limits, &mut NoProgress);
+        }
+        match std::fs::read_to_string(input) {
+            Ok(input) => runner::run(solution, &input, &mut NoProgress),
+            Err(e) => Outcome::Failed(e.to_string()),
+        }
+    });
+    match batch {
+        Ok(batch) => {
+            println!("{}", batch);
+            if batch.failures().next().is_some() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("could not read {}: {}", dir.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Solves from `path`, or standard input for `-`, in this process. Days with a
+// streaming solver never hold the whole input; the others read it all first.
+// Nothing is linted, cached or put into the run history, since the input cannot
+// be hashed before it is consumed.
+fn run_streamed(solution: &Solution, path: &Path) -> Outcome {
+    let mut reader = match stream::open(path) {
+        Ok(reader) => reader,
+        Err(e) => return Outcome::Failed(format!("{}: {}", path.display(), e)),
+    };
+    match registry::streaming(solution.day, solution.part) {
+        Some(streaming) => runner::run_stream(streaming, &mut reader),
+        None => {
+            let mut input = String::new();
+            match reader.read_to_string(&mut input) {
+                Ok(_) => runner::run(solution, &input, &mut NoProgress),
+                Err(e) => Outcome::Failed(e.to_string()),
+            }
+        }
+    }
+}
+
+fn visualize(args: &RunArgs) {
+    let days = registry::DAYS
+        .iter()
+        .filter(|d| args.day.is_none_or(|day| d.day == day));
+    for day in days {
+        // --input fixes the day, so it is the only one pictured.
+        let path = args
+            .input
+            .clone()
+            .unwrap_or_else(|| registry::input_path(day.day));
+        let input = match std::fs::read_to_string(&path) {
+            Ok(input) => input,
+            // Days without an input of their own are simply skipped.
+            Err(_) if args.input.is_none() => continue,
+            Err(e) => {
+                eprintln!("could not read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if let (true, Some(render)) = (args.visualize, day.visualize_terminal) {
+            println!("Day {}:", day.day);
+            print!("{}", render(&input));
+        }
+        if let (Some(path), Some(render)) = (&args.svg, day.visualize) {
+            match std::fs::write(path, render(&input)) {
+                Ok(()) => println!("Wrote {}", path.display()),
+                Err(e) => eprintln!("could not write {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+fn show_history() {
+    match history::load(Path::new(history::HISTORY_PATH)) {
+        Ok(records) if records.is_empty() => println!("no runs recorded yet"),
+        Ok(records) => print!("{}", history::report(&records)),
+        Err(e) => {
+            eprintln!("could not read run history: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn report(args: &[String]) {
+    let mut out = PathBuf::from(report::REPORT_PATH);
+    let mut limits = Limits::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--out" => out = PathBuf::from(value()),
+            arg if parse_limit_arg(arg, &mut value, &mut limits) => (),
+            _ => usage(),
+        }
+    }
+    let answers = Answers::load(Path::new(answers::ANSWERS_PATH)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let exe = std::env::current_exe().unwrap();
+    let days = registry::DAYS
+        .iter()
+        .map(|day| {
+            eprintln!("Running day {}...", day.day);
+            report::build(day, &exe, &limits, &answers)
+        })
+        .collect::<Vec<_>>();
+    if let Err(e) = std::fs::write(&out, report::render(&days)) {
+        eprintln!("could not write {}: {}", out.display(), e);
+        std::process::exit(1);
+    }
+    println!("Wrote {}", out.display());
+}
+
+fn watch(args: &[String]) {
+    let mut day = None;
+    let mut limits = Limits::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--day" => day = Some(value().parse().unwrap_or_else(|_| usage())),
+            arg if parse_limit_arg(arg, &mut value, &mut limits) => (),
+            _ => usage(),
+        }
+    }
+    let day = day.unwrap_or_else(|| usage());
+    if registry::day(day).is_none() {
+        eprintln!("day {} is not registered", day);
+        std::process::exit(1);
+    }
+    advent::watch::watch(day, &limits);
+}
+
+fn query(args: &[String]) {
+    let mut day = None;
+    let mut seed = None;
+    let mut kind = InputKind::Real;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--day" => day = Some(value().parse::<u8>().unwrap_or_else(|_| usage())),
+            "--seed" => seed = Some(value().parse::<usize>().unwrap_or_else(|_| usage())),
+            "--example" => kind = InputKind::Example,
+            _ => usage(),
+        }
+    }
+    let (Some(5), Some(seed)) = (day, seed) else {
+        eprintln!("only day 5 supports queries: query --day 5 --seed N");
+        std::process::exit(2);
+    };
+    let path = kind.path(5);
+    let input = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("could not read {}: {}", path.display(), e);
+        std::process::exit(1);
+    });
+    let (_seeds, maps) = day5::parse_input(&input);
+    let steps = day5::Almanac::new(maps)
+        .and_then(|almanac| {
+            let path = almanac.seed_to_location()?;
+            Ok(day5::trace(seed, &path)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>())
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("{}: {}", path.display(), e);
+            std::process::exit(1);
+        });
+    for step in steps {
+        println!("{}", step);
+    }
+}
+
+// Checks day 7's hand classifier on every hand, with and without jokers.
+fn verify_hands(args: &[String]) {
+    if !args.is_empty() {
+        usage();
+    }
+    let mut agrees = true;
+    for jokers in [false, true] {
+        if jokers {
+            println!();
+        }
+        let verification = verify::day7::verify(jokers);
+        println!("{}", verification);
+        for mismatch in &verification.mismatches {
+            println!(
+                "  {}: expected {:?}, got {:?}",
+                mismatch.hand, mismatch.expected, mismatch.actual
+            );
+        }
+        agrees &= verification.mismatches.is_empty();
+    }
+    if !agrees {
+        std::process::exit(1);
+    }
+}
+
+fn lint(args: &[String]) {
+    let mut day = None;
+    let mut kind = InputKind::Real;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => {
+                let value = args.next().unwrap_or_else(|| usage());
+                day = Some(value.parse::<u8>().unwrap_or_else(|_| usage()));
+            }
+            "--example" => kind = InputKind::Example,
+            _ => usage(),
+        }
+    }
+    let day = day.unwrap_or_else(|| usage());
+    let Some(lint) = registry::day(day).and_then(|d| d.lint) else {
+        println!("Day {} has no lint", day);
+        return;
+    };
+    let path = kind.path(day);
+    let input = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("could not read {}: {}", path.display(), e);
+        std::process::exit(1);
+    });
+    let report = lint(&input);
+    for note in &report.notes {
+        println!("{}", note);
+    }
+    for problem in &report.problems {
+        println!("problem: {}", problem);
+    }
+    if report.problems.is_empty() {
+        println!("no problems found");
+    } else {
+        std::process::exit(1);
+    }
+}
+
+fn generate(args: &[String]) {
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = 1.0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--day" => day = Some(value().parse::<u8>().unwrap_or_else(|_| usage())),
+            "--seed" => seed = value().parse().unwrap_or_else(|_| usage()),
+            "--size" => size = value().parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+    let day = day.unwrap_or_else(|| usage());
+    match gen::generate(day, seed, size) {
+        Some(input) => print!("{}", input),
+        None => {
+            eprintln!("Day {} has no generator", day);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn rank(args: &[String]) {
+    let mut kind = InputKind::Real;
+    let mut csv = None;
+    let mut json = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--example" => kind = InputKind::Example,
+            "--csv" => csv = Some(PathBuf::from(value())),
+            "--json" => json = Some(PathBuf::from(value())),
+            _ => usage(),
+        }
+    }
+    let path = kind.path(7);
+    let input = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("could not read {}: {}", path.display(), e);
+        std::process::exit(1);
+    });
+    let ranking = day7::Ranking::new(day7::parse_input(&input));
+    let exports = [(csv, ranking.to_csv()), (json, ranking.to_json())];
+    if exports.iter().all(|(path, _)| path.is_none()) {
+        println!("{}", ranking);
+    }
+    for (path, contents) in exports {
+        let Some(path) = path else { continue };
+        match std::fs::write(&path, contents) {
+            Ok(()) => println!("Wrote {}", path.display()),
+            Err(e) => eprintln!("could not write {}: {}", path.display(), e),
+        }
+    }
+}
 
 pub fn main() {
-    advent::day7::main();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some(runner::CHILD_COMMAND) => runner::child_main(&args[1..]),
+        Some("run") => run(&args[1..]),
+        Some("history") => show_history(),
+        Some("cache") => cache_command(&args[1..]),
+        Some("report") => report(&args[1..]),
+        Some("watch") => watch(&args[1..]),
+        Some("query") => query(&args[1..]),
+        Some("lint") => lint(&args[1..]),
+        Some("gen") => generate(&args[1..]),
+        Some("rank") => rank(&args[1..]),
+        Some("verify") => verify_hands(&args[1..]),
+        #[cfg(feature = "tui")]
+        Some("tui") => {
+            if let Err(e) = advent::tui::run() {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        None => run(&[]),
+        _ => usage(),
+    }
 }
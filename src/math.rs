@@ -0,0 +1,211 @@
+//! Number theory: gcd and lcm, the Chinese Remainder Theorem, integer square
+//! roots and modular arithmetic.
+//!
+//! Anything that can fail, by overflowing or by a zero modulus, returns `None`
+//! instead of wrapping or panicking.
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or `None` if it does not fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The least common multiple of all `values`, 1 for none at all.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// Solves `x = residue (mod modulus)` for every pair at once.
+///
+/// Moduli need not be coprime. Returns `(x, m)` with `x < m`, where `m` is the
+/// lcm of the moduli and every solution is `x` plus a multiple of `m`, or `None`
+/// if the congruences contradict each other or `m` overflows.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let mut x: u64 = 0;
+    let mut m: u64 = 1;
+    for &(residue, modulus) in congruences {
+        if modulus == 0 {
+            return None;
+        }
+        let residue = residue % modulus;
+        let (g, p, _) = extended_gcd(m as i128, modulus as i128);
+        let difference = residue as i128 - x as i128;
+        if difference % g != 0 {
+            return None;
+        }
+        // x + m * k solves the new congruence for k = difference / g * p (mod modulus / g).
+        let step = (modulus / g as u64) as i128;
+        let k = ((difference / g) % step * (p % step)).rem_euclid(step);
+        let combined = lcm(m, modulus)?;
+        x = ((x as i128 + m as i128 * k) % combined as i128) as u64;
+        m = combined;
+    }
+    Some((x, m))
+}
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above never overshoots, so it stops on the floor.
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt(n: u64) -> u64 {
+    isqrt_u128(n as u128) as u64
+}
+
+/// `base` to the power `exponent`, modulo `modulus`, or `None` for modulus 0.
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> Option<u64> {
+    match modulus {
+        0 => return None,
+        1 => return Some(0),
+        _ => (),
+    }
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1u128;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    Some(result as u64)
+}
+
+/// The `x < modulus` with `a * x = 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a as i128, modulus as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus as i128) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+
+    fn naive_gcd(a: u64, b: u64) -> u64 {
+        (1..=a.max(b))
+            .rev()
+            .find(|&d| a.is_multiple_of(d) && b.is_multiple_of(d))
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        let mut rng = Rng::new(42);
+        for _ in 0..500 {
+            let (a, b) = (rng.below(300) as u64, rng.below(300) as u64);
+            assert_eq!(gcd(a, b), naive_gcd(a, b));
+            let naive_lcm = (1..=a * b).find(|m| m % a == 0 && m % b == 0).unwrap_or(0);
+            assert_eq!(lcm(a, b), Some(naive_lcm));
+            let (g, x, y) = extended_gcd(a as i128, b as i128);
+            assert_eq!(g, gcd(a, b) as i128);
+            assert_eq!(a as i128 * x + b as i128 * y, g);
+        }
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        let mut rng = Rng::new(7);
+        for _ in 0..300 {
+            let congruences = (0..rng.between(1, 3))
+                .map(|_| {
+                    let modulus = rng.between(1, 12) as u64;
+                    (rng.below(20) as u64, modulus)
+                })
+                .collect::<Vec<_>>();
+            let m = lcm_all(congruences.iter().map(|c| c.1)).unwrap();
+            let naive = (0..m).find(|x| congruences.iter().all(|&(r, n)| x % n == r % n));
+            assert_eq!(
+                crt(&congruences),
+                naive.map(|x| (x, m)),
+                "{:?}",
+                congruences
+            );
+        }
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, 0)]), None);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        let mut rng = Rng::new(3);
+        for n in (0..2000).chain((0..2000).map(|_| rng.next_u64())) {
+            let r = isqrt(n) as u128;
+            assert!(r * r <= n as u128 && (r + 1) * (r + 1) > n as u128, "{}", n);
+        }
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt_u128(u128::MAX), u64::MAX as u128);
+        let big = (1u128 << 60) + 12345;
+        let r = isqrt_u128(big * big);
+        assert_eq!(r, big);
+        assert_eq!(isqrt_u128(big * big - 1), big - 1);
+    }
+
+    #[test]
+    fn test_modular() {
+        let mut rng = Rng::new(11);
+        for _ in 0..500 {
+            let modulus = rng.between(1, 50) as u64;
+            let (base, exponent) = (rng.next_u64(), rng.below(20) as u64);
+            let naive = (0..exponent).fold(1 % modulus, |acc, _| {
+                (acc as u128 * (base % modulus) as u128 % modulus as u128) as u64
+            });
+            assert_eq!(mod_pow(base, exponent, modulus), Some(naive));
+
+            let a = rng.below(100) as u64;
+            let naive = (0..modulus).find(|x| a * x % modulus == 1 % modulus);
+            let naive = naive.filter(|_| gcd(a, modulus) == 1);
+            assert_eq!(mod_inverse(a, modulus), naive, "{} mod {}", a, modulus);
+        }
+        assert_eq!(mod_pow(3, 4, 0), None);
+        assert_eq!(mod_inverse(3, 0), None);
+    }
+}
@@ -0,0 +1,279 @@
+//! Standard poker hand evaluation with suits, straights and flushes.
+//!
+//! Ranks reuse `day7::Card`, except that a jack is 11 here rather than Camel
+//! Cards' joker rank of 1.
+
+use crate::day7::{Card, HandType};
+use itertools::Itertools;
+use std::cmp::{Ordering, Reverse};
+use std::fmt::{self, Formatter};
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub fn from_char(c: char) -> Option<Suit> {
+        match c.to_ascii_lowercase() {
+            'c' => Some(Suit::Clubs),
+            'd' => Some(Suit::Diamonds),
+            'h' => Some(Suit::Hearts),
+            's' => Some(Suit::Spades),
+            _ => None,
+        }
+    }
+}
+
+/// A card with a suit, written like `Ah` or `Ts`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct PlayingCard {
+    pub card: Card,
+    pub suit: Suit,
+}
+
+impl PlayingCard {
+    pub fn parse(text: &str) -> Result<PlayingCard, PokerError> {
+        let invalid = || PokerError::InvalidCard(text.to_string());
+        let mut chars = text.chars();
+        let (Some(rank), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(invalid());
+        };
+        Ok(PlayingCard {
+            card: Card::from_poker_char(rank.to_ascii_uppercase()).ok_or_else(invalid)?,
+            suit: Suit::from_char(suit).ok_or_else(invalid)?,
+        })
+    }
+}
+
+impl fmt::Display for PlayingCard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let suit = match self.suit {
+            Suit::Clubs => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Hearts => 'h',
+            Suit::Spades => 's',
+        };
+        write!(f, "{}{}", self.card.to_char(), suit)
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PokerError {
+    #[error("invalid card {0:?}")]
+    InvalidCard(String),
+    #[error("a hand needs 5 to 7 cards, not {0}")]
+    HandSize(usize),
+    #[error("{0} appears twice")]
+    DuplicateCard(PlayingCard),
+}
+
+/// Poker hand categories from worst to best. The names shared with
+/// `day7::HandType` mean the same thing.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl Category {
+    /// The category a Camel Cards hand type names, if poker has one. Five of a
+    /// kind needs a wild card, and this evaluator has none.
+    pub fn from_hand_type(hand_type: HandType) -> Option<Category> {
+        match hand_type {
+            HandType::HighCard => Some(Category::HighCard),
+            HandType::OnePair => Some(Category::OnePair),
+            HandType::TwoPair => Some(Category::TwoPair),
+            HandType::ThreeOfAKind => Some(Category::ThreeOfAKind),
+            HandType::FullHouse => Some(Category::FullHouse),
+            HandType::FourOfAKind => Some(Category::FourOfAKind),
+            HandType::FiveOfAKind => None,
+        }
+    }
+}
+
+/// The value of a hand. Comparing evaluations ranks hands: first by category,
+/// then by the ranks that break ties within it. Suits never break ties.
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub category: Category,
+    /// Ranks in the order they are compared, e.g. trips before kickers.
+    pub ranks: Vec<u8>,
+    /// The five cards that make the hand, in the same order as `ranks`.
+    pub cards: Vec<PlayingCard>,
+}
+
+impl PartialEq for Evaluation {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Evaluation {}
+
+impl PartialOrd for Evaluation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Evaluation {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.category, &self.ranks).cmp(&(other.category, &other.ranks))
+    }
+}
+
+/// Parses a hand written as space-separated cards, like `Ah Kh Qh Jh Th`.
+pub fn parse_hand(text: &str) -> Result<Vec<PlayingCard>, PokerError> {
+    text.split_whitespace().map(PlayingCard::parse).collect()
+}
+
+/// The best five-card hand among `cards`, which may hold 5 to 7 cards.
+pub fn evaluate(cards: &[PlayingCard]) -> Result<Evaluation, PokerError> {
+    if !(5..=7).contains(&cards.len()) {
+        return Err(PokerError::HandSize(cards.len()));
+    }
+    if let Some(card) = cards.iter().duplicates().next() {
+        return Err(PokerError::DuplicateCard(*card));
+    }
+    Ok(cards
+        .iter()
+        .copied()
+        .combinations(5)
+        .map(|five| evaluate_five(&five))
+        .max()
+        .unwrap())
+}
+
+fn evaluate_five(cards: &[PlayingCard]) -> Evaluation {
+    // Most common rank first, higher rank first among equally common ones.
+    let mut cards = cards.to_vec();
+    let count = |rank: u8| cards.iter().filter(|c| c.card.rank == rank).count();
+    let mut counts = cards.iter().map(|c| count(c.card.rank)).collect::<Vec<_>>();
+    let mut order = (0..5).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse((counts[i], cards[i].card.rank)));
+    cards = order.iter().map(|&i| cards[i]).collect();
+    let mut ranks = cards.iter().map(|c| c.card.rank).collect::<Vec<_>>();
+
+    let flush = cards.iter().all(|c| c.suit == cards[0].suit);
+    let distinct = ranks.iter().all_unique();
+    let straight = distinct && (ranks[0] - ranks[4] == 4 || ranks == [14, 5, 4, 3, 2]);
+    if straight && ranks[0] == 14 && ranks[1] == 5 {
+        // The wheel: the ace plays low.
+        ranks.rotate_left(1);
+        cards.rotate_left(1);
+    }
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    let category = match (straight, flush, counts[0], counts[3]) {
+        (true, true, _, _) => Category::StraightFlush,
+        (_, _, 4, _) => Category::FourOfAKind,
+        (_, _, 3, 2) => Category::FullHouse,
+        (_, true, _, _) => Category::Flush,
+        (true, _, _, _) => Category::Straight,
+        (_, _, 3, _) => Category::ThreeOfAKind,
+        (_, _, 2, 2) => Category::TwoPair,
+        (_, _, 2, _) => Category::OnePair,
+        _ => Category::HighCard,
+    };
+    Evaluation {
+        category,
+        ranks,
+        cards,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(text: &str) -> Evaluation {
+        evaluate(&parse_hand(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_categories() {
+        let cases = [
+            ("2h 7d 9c Js Kh", Category::HighCard),
+            ("2h 2d 9c Js Kh", Category::OnePair),
+            ("2h 2d 9c 9s Kh", Category::TwoPair),
+            ("2h 2d 2c Js Kh", Category::ThreeOfAKind),
+            ("Ah 2d 3c 4s 5h", Category::Straight),
+            ("Th Jd Qc Ks Ah", Category::Straight),
+            ("2h 7h 9h Jh Kh", Category::Flush),
+            ("2h 2d 2c Ks Kh", Category::FullHouse),
+            ("2h 2d 2c 2s Kh", Category::FourOfAKind),
+            ("9s Ts Js Qs Ks", Category::StraightFlush),
+        ];
+        for (hand, category) in cases {
+            assert_eq!(eval(hand).category, category, "{}", hand);
+        }
+        assert_eq!(eval("Qh Kd Ac 2s 3h").category, Category::HighCard);
+    }
+
+    #[test]
+    fn test_order() {
+        let ordered = [
+            "2h 3d 4c 5s 7h",
+            "Ah Kd Qc Js 9h",
+            "2h 2d 4c 5s 7h",
+            "2h 2d Ac Ks Qh",
+            "3h 3d 2c 4s 5h",
+            "3h 3d 2c 2s 5h",
+            "3h 3d 4c 4s 2h",
+            "Ah 2d 3c 4s 5h",
+            "2h 3d 4c 5s 6h",
+            "Th Jd Qc Ks Ah",
+            "2h 3h 4h 5h 7h",
+            "2h 2d 2c 3s 3h",
+            "2h 2d 2c 2s 3h",
+            "Ah 2h 3h 4h 5h",
+            "Th Jh Qh Kh Ah",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(eval(pair[0]) < eval(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(eval("2h 3d 4c 5s 7h"), eval("2c 3s 4d 5h 7c"));
+    }
+
+    #[test]
+    fn test_best_of_seven() {
+        let best = eval("2h 2d 9h Jh Qh Kh 3c");
+        assert_eq!(best.category, Category::Flush);
+        assert_eq!(best.ranks, vec![13, 12, 11, 9, 2]);
+        assert_eq!(eval("Ah Ad Ac Kh Kd Ks 2c").ranks, vec![14, 14, 14, 13, 13]);
+        assert_eq!(eval("5c 4d 3h 2s Ac Kd 6h").ranks, vec![6, 5, 4, 3, 2]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let hand = parse_hand("2h 3d 4c 5s").unwrap();
+        assert_eq!(evaluate(&hand), Err(PokerError::HandSize(4)));
+        let hand = parse_hand("2h 3d 4c 5s 2h").unwrap();
+        assert!(matches!(evaluate(&hand), Err(PokerError::DuplicateCard(_))));
+        assert_eq!(
+            parse_hand("2h 1d"),
+            Err(PokerError::InvalidCard("1d".to_string()))
+        );
+        assert_eq!(PlayingCard::parse("Td").unwrap().to_string(), "Td");
+    }
+
+    #[test]
+    fn test_camel_card_types() {
+        assert_eq!(
+            Category::from_hand_type(HandType::FullHouse),
+            Some(Category::FullHouse)
+        );
+        assert_eq!(Category::from_hand_type(HandType::FiveOfAKind), None);
+    }
+}
@@ -0,0 +1,199 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Returned by a solver that stopped early because its `Progress` asked it to.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("solver was cancelled")]
+pub struct Cancelled;
+
+/// A structured progress update emitted by a long-running solver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The solver entered a new phase of work, e.g. "expanding seeds".
+    Stage(String),
+    /// `done` out of `total` units of the current stage are finished.
+    Step { done: usize, total: usize },
+    /// A named intermediate value, e.g. the best answer found so far.
+    Value { name: String, value: usize },
+    /// A free-form line explaining what the solver is doing.
+    Note(String),
+}
+
+/// Sink for progress events that also decides whether the solver should keep going.
+pub trait Progress {
+    fn event(&mut self, event: Event);
+
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    /// Reports a step and returns an error if the solver should stop.
+    fn step(&mut self, done: usize, total: usize) -> Result<(), Cancelled> {
+        self.event(Event::Step { done, total });
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+        Ok(())
+    }
+}
+
+/// Discards all events and never cancels. Used by tests and quiet runs.
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn event(&mut self, _event: Event) {}
+}
+
+/// Records every event, handy for asserting on what a solver reported.
+impl Progress for Vec<Event> {
+    fn event(&mut self, event: Event) {
+        self.push(event);
+    }
+}
+
+/// Shared flag that lets another thread (or a signal handler) stop a solver.
+#[derive(Debug, Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Renders progress events as a single-line bar on stderr.
+pub struct ProgressBar {
+    width: usize,
+    stage: String,
+    percent: Option<usize>,
+    values: Vec<(String, usize)>,
+    cancel: CancelFlag,
+}
+
+impl ProgressBar {
+    pub fn new() -> Self {
+        Self::with_cancel(CancelFlag::new())
+    }
+
+    pub fn with_cancel(cancel: CancelFlag) -> Self {
+        Self {
+            width: 30,
+            stage: String::new(),
+            percent: None,
+            values: Vec::new(),
+            cancel,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let percent = self.percent.unwrap_or(0);
+        let filled = percent * self.width / 100;
+        let mut line = format!(
+            "[{}{}] {:>3}% {}",
+            "#".repeat(filled),
+            "-".repeat(self.width - filled),
+            percent,
+            self.stage
+        );
+        for (name, value) in &self.values {
+            line.push_str(&format!(" {}={}", name, value));
+        }
+        line
+    }
+
+    fn draw(&self) {
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r\x1b[2K{}", self.render());
+        let _ = stderr.flush();
+    }
+
+    pub fn finish(&self) {
+        if self.percent.is_some() || !self.stage.is_empty() {
+            eprintln!();
+        }
+    }
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Progress for ProgressBar {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Stage(stage) => {
+                self.stage = stage;
+                self.percent = None;
+            }
+            Event::Step { done, total } => {
+                let percent = (done * 100).checked_div(total).unwrap_or(100);
+                // Only redraw when the visible percentage moves.
+                if self.percent == Some(percent) {
+                    return;
+                }
+                self.percent = Some(percent);
+            }
+            Event::Value { name, value } => {
+                match self.values.iter_mut().find(|(n, _)| *n == name) {
+                    Some(entry) => entry.1 = value,
+                    None => self.values.push((name, value)),
+                }
+            }
+            // Notes scroll above the bar instead of being drawn into it.
+            Event::Note(note) => eprint!("\r\x1b[2K{}\n", note),
+        }
+        self.draw();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_set()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Cancellable(CancelFlag);
+
+    impl Progress for Cancellable {
+        fn event(&mut self, _event: Event) {}
+
+        fn is_cancelled(&self) -> bool {
+            self.0.is_set()
+        }
+    }
+
+    #[test]
+    fn test_step_stops_when_cancelled() {
+        let cancel = CancelFlag::new();
+        let mut progress = Cancellable(cancel.clone());
+        assert_eq!(progress.step(1, 10), Ok(()));
+        cancel.cancel();
+        assert_eq!(progress.step(2, 10), Err(Cancelled));
+    }
+
+    #[test]
+    fn test_render_bar() {
+        let mut bar = ProgressBar::new();
+        bar.stage = "searching".to_string();
+        bar.percent = Some(50);
+        bar.values.push(("min".to_string(), 35));
+        assert_eq!(
+            bar.render(),
+            "[###############---------------]  50% searching min=35"
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use crate::error::SolveError;
+use crate::gen::{self, Rng};
+use crate::lint::LintReport;
+use crate::progress::Progress;
+use crate::{day1, day2, day3, day4, day5, day6, day7, viz};
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+
+pub type Solver = fn(&str, &mut dyn Progress) -> Result<usize, SolveError>;
+
+/// Solves from a reader a line at a time, without loading the whole input.
+pub type StreamSolver = fn(&mut dyn BufRead) -> io::Result<usize>;
+
+/// Name of the variant the CLI, reports and history run for each day/part.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// One solved part of one day, callable through a uniform signature.
+///
+/// A day/part may have several variants, e.g. a brute force and an optimised
+/// solver, that must all give the same answer.
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub variant: &'static str,
+    pub solve: Solver,
+}
+
+impl Solution {
+    pub fn input_path(&self) -> PathBuf {
+        input_path(self.day)
+    }
+}
+
+pub fn input_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("data/input{}.txt", day))
+}
+
+pub fn example_input_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("data/test_input{}.txt", day))
+}
+
+/// Which of a day's input files a run uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InputKind {
+    Real,
+    Example,
+}
+
+impl InputKind {
+    pub fn path(self, day: u8) -> PathBuf {
+        match self {
+            InputKind::Real => input_path(day),
+            InputKind::Example => example_input_path(day),
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            InputKind::Real => InputKind::Example,
+            InputKind::Example => InputKind::Real,
+        }
+    }
+}
+
+/// Hooks that belong to a day as a whole rather than to one of its parts.
+pub struct Day {
+    pub day: u8,
+    /// Parses the input and drops the result, so the parser can be timed alone.
+    pub parse: Option<fn(&str)>,
+    /// Renders an SVG picture of the input, for days that have one.
+    pub visualize: Option<fn(&str) -> String>,
+    /// Renders the same picture with ANSI colours for the terminal.
+    pub visualize_terminal: Option<fn(&str) -> String>,
+    /// Checks the input for problems before it is solved.
+    pub lint: Option<fn(&str) -> LintReport>,
+    /// Generates a random input, scaled by a size where 1 is about as big as a
+    /// real input.
+    pub generate: Option<fn(&mut Rng, f64) -> String>,
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        parse: None,
+        visualize: None,
+        visualize_terminal: None,
+        lint: None,
+        generate: Some(gen::sized_calibration),
+    },
+    Day {
+        day: 2,
+        parse: Some(|input| {
+            day2::parse_input(input);
+        }),
+        visualize: None,
+        visualize_terminal: None,
+        lint: None,
+        generate: Some(gen::sized_games),
+    },
+    Day {
+        day: 3,
+        parse: Some(|input| {
+            day3::parse_input(input);
+        }),
+        visualize: Some(viz::day3::visualize),
+        visualize_terminal: Some(viz::day3::visualize_terminal),
+        lint: None,
+        generate: Some(gen::sized_schematic),
+    },
+    Day {
+        day: 4,
+        parse: Some(|input| {
+            day4::parse_input(input);
+        }),
+        visualize: None,
+        visualize_terminal: None,
+        lint: Some(day4::lint),
+        generate: Some(gen::sized_deck),
+    },
+    Day {
+        day: 5,
+        parse: Some(|input| {
+            day5::parse_input(input);
+        }),
+        visualize: Some(viz::day5::visualize),
+        visualize_terminal: Some(viz::day5::visualize_terminal),
+        lint: Some(day5::lint),
+        generate: Some(gen::sized_almanac),
+    },
+    Day {
+        day: 6,
+        parse: Some(|input| {
+            day6::parse_input(input);
+        }),
+        visualize: None,
+        visualize_terminal: None,
+        lint: None,
+        generate: Some(gen::sized_race_sheet),
+    },
+    Day {
+        day: 7,
+        parse: Some(|input| {
+            day7::parse_input(input);
+        }),
+        visualize: None,
+        visualize_terminal: None,
+        lint: None,
+        generate: Some(gen::sized_hands),
+    },
+];
+
+pub fn day(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+// Day 3 star 2 and day 7 star 1 are not implemented yet, so they are not listed.
+pub const SOLUTIONS: &[Solution] = &[
+    Solution {
+        day: 1,
+        part: 1,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day1::find_solution(input, false) as usize),
+    },
+    Solution {
+        day: 1,
+        part: 2,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day1::find_solution(input, true) as usize),
+    },
+    Solution {
+        day: 2,
+        part: 1,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day2::find_solution(input, false)),
+    },
+    Solution {
+        day: 2,
+        part: 2,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day2::find_solution(input, true)),
+    },
+    Solution {
+        day: 3,
+        part: 1,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day3::solve(input, false) as usize),
+    },
+    Solution {
+        day: 4,
+        part: 1,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day4::solve(input) as usize),
+    },
+    Solution {
+        day: 4,
+        part: 2,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day4::solve_star2(input) as usize),
+    },
+    Solution {
+        day: 4,
+        part: 2,
+        variant: "array",
+        solve: |input, _| Ok(day4::solve_star2_array(input) as usize),
+    },
+    Solution {
+        day: 5,
+        part: 1,
+        variant: DEFAULT_VARIANT,
+        solve: day5::solve,
+    },
+    Solution {
+        day: 5,
+        part: 2,
+        variant: DEFAULT_VARIANT,
+        solve: day5::solve_star2,
+    },
+    Solution {
+        day: 5,
+        part: 2,
+        variant: "intervals",
+        solve: day5::solve_star2_intervals,
+    },
+    Solution {
+        day: 6,
+        part: 1,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day6::solve6(input)),
+    },
+    Solution {
+        day: 6,
+        part: 1,
+        variant: "closed-form",
+        solve: |input, _| Ok(day6::solve6_closed_form(input)),
+    },
+    Solution {
+        day: 6,
+        part: 2,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day6::solve6_star2(input)),
+    },
+    Solution {
+        day: 6,
+        part: 2,
+        variant: "closed-form",
+        solve: |input, _| Ok(day6::solve6_star2_closed_form(input)),
+    },
+    Solution {
+        day: 7,
+        part: 2,
+        variant: DEFAULT_VARIANT,
+        solve: |input, _| Ok(day7::solve_star2(input)),
+    },
+];
+
+/// The default variant of every registered day/part.
+pub fn defaults() -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS.iter().filter(|s| s.variant == DEFAULT_VARIANT)
+}
+
+pub fn find(day: u8, part: u8) -> Option<&'static Solution> {
+    find_variant(day, part, DEFAULT_VARIANT)
+}
+
+pub fn find_variant(day: u8, part: u8, variant: &str) -> Option<&'static Solution> {
+    SOLUTIONS
+        .iter()
+        .find(|s| s.day == day && s.part == part && s.variant == variant)
+}
+
+pub fn for_day(day: u8) -> impl Iterator<Item = &'static Solution> {
+    defaults().filter(move |s| s.day == day)
+}
+
+/// Every variant of one day/part, default first.
+pub fn variants(day: u8, part: u8) -> Vec<&'static Solution> {
+    SOLUTIONS
+        .iter()
+        .filter(|s| s.day == day && s.part == part)
+        .collect()
+}
+
+/// A default solver that can also read its input as a stream. Only the
+/// line-oriented days have one.
+pub struct Streaming {
+    pub day: u8,
+    pub part: u8,
+    pub solve: StreamSolver,
+}
+
+pub const STREAMING: &[Streaming] = &[
+    Streaming {
+        day: 1,
+        part: 1,
+        solve: |reader| Ok(day1::find_solution_reader(reader, false)? as usize),
+    },
+    Streaming {
+        day: 1,
+        part: 2,
+        solve: |reader| Ok(day1::find_solution_reader(reader, true)? as usize),
+    },
+    Streaming {
+        day: 2,
+        part: 1,
+        solve: |reader| day2::find_solution_reader(reader, false),
+    },
+    Streaming {
+        day: 2,
+        part: 2,
+        solve: |reader| day2::find_solution_reader(reader, true),
+    },
+    Streaming {
+        day: 4,
+        part: 1,
+        solve: |reader| Ok(day4::solve_reader(reader)? as usize),
+    },
+    Streaming {
+        day: 4,
+        part: 2,
+        solve: |reader| Ok(day4::solve_star2_reader(reader)? as usize),
+    },
+    Streaming {
+        day: 7,
+        part: 2,
+        solve: |reader| day7::solve_star2_reader(reader),
+    },
+];
+
+pub fn streaming(day: u8, part: u8) -> Option<&'static Streaming> {
+    STREAMING.iter().find(|s| s.day == day && s.part == part)
+}
@@ -0,0 +1,217 @@
+use crate::answers::{Answers, Verdict};
+use crate::progress::NoProgress;
+use crate::registry::{self, Day};
+use crate::runner::{self, Limits, Outcome};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub const REPORT_PATH: &str = "report.html";
+
+const STYLE: &str = "body{font-family:sans-serif;background:#0f0f23;color:#ccc;margin:2em}\
+h1{color:#00cc00}\
+.days{display:flex;flex-wrap:wrap;gap:1em}\
+.card{background:#1a1a3a;border:1px solid #333;border-radius:6px;padding:1em;min-width:20em}\
+.card h2{margin-top:0;color:#ffff66}\
+table{border-collapse:collapse;width:100%}\
+td,th{padding:.2em .6em;text-align:left}\
+.correct{color:#00cc00}.wrong{color:#ff4444}.unknown{color:#999}\
+.viz{margin-top:1em;overflow:auto;max-height:30em}\
+.viz svg{max-width:100%;height:auto}";
+
+pub struct PartReport {
+    pub part: u8,
+    pub outcome: Outcome,
+    pub verdict: Verdict,
+}
+
+pub struct DayReport {
+    pub day: u8,
+    pub parse: Option<Duration>,
+    pub parts: Vec<PartReport>,
+    pub visualization: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Runs every part of `day` in isolation and collects everything its card shows.
+pub fn build(day: &Day, exe: &Path, limits: &Limits, answers: &Answers) -> DayReport {
+    let input_path = registry::input_path(day.day);
+    let input = match std::fs::read_to_string(&input_path) {
+        Ok(input) => input,
+        Err(e) => {
+            return DayReport {
+                day: day.day,
+                parse: None,
+                parts: Vec::new(),
+                visualization: None,
+                error: Some(format!("{}: {}", input_path.display(), e)),
+            }
+        }
+    };
+    // The parser and visualiser run in this process, so a panic in either is
+    // caught and reported on the card instead of aborting the whole report.
+    let mut errors = Vec::new();
+    let parse = day.parse.and_then(|parse| {
+        let start = Instant::now();
+        catch("parser", || parse(&input))
+            .map_err(|e| errors.push(e))
+            .ok()
+            .map(|()| start.elapsed())
+    });
+    let parts = registry::for_day(day.day)
+        .map(|solution| {
+            let outcome = runner::run_isolated(exe, solution, &input_path, limits, &mut NoProgress);
+            let verdict = answers.check(day.day, solution.part, outcome.answer());
+            PartReport {
+                part: solution.part,
+                outcome,
+                verdict,
+            }
+        })
+        .collect();
+    let visualization = day.visualize.and_then(|visualize| {
+        catch("visualiser", || visualize(&input))
+            .map_err(|e| errors.push(e))
+            .ok()
+    });
+    DayReport {
+        day: day.day,
+        parse,
+        parts,
+        visualization,
+        error: (!errors.is_empty()).then(|| errors.join("; ")),
+    }
+}
+
+fn catch<T>(what: &str, f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| format!("{} panicked: {}", what, runner::panic_message(&*payload)))
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_part(part: &PartReport) -> String {
+    let (answer, time) = match &part.outcome {
+        Outcome::Solved { answer, elapsed } => (answer.to_string(), format!("{:.2?}", elapsed)),
+        other => (other.to_string(), "-".to_string()),
+    };
+    let (class, status) = match part.verdict {
+        Verdict::Correct => ("correct", "&#10004; correct".to_string()),
+        Verdict::Wrong { expected } => ("wrong", format!("&#10008; expected {}", expected)),
+        Verdict::Unknown => ("unknown", "? unverified".to_string()),
+    };
+    format!(
+        "<tr><td>Part {}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td></tr>",
+        part.part,
+        escape(&answer),
+        class,
+        status,
+        time
+    )
+}
+
+fn render_day(day: &DayReport) -> String {
+    let mut card = format!("<div class=\"card\"><h2>Day {}</h2>", day.day);
+    if let Some(error) = &day.error {
+        card.push_str(&format!("<p class=\"wrong\">{}</p>", escape(error)));
+        // Without an input there is nothing else to show.
+        if day.parts.is_empty() {
+            card.push_str("</div>");
+            return card;
+        }
+    }
+    card.push_str("<table><tr><th></th><th>Answer</th><th>Status</th><th>Time</th></tr>");
+    let parse = day.parse.map_or("-".to_string(), |d| format!("{:.2?}", d));
+    card.push_str(&format!(
+        "<tr><td>Parse</td><td></td><td></td><td>{}</td></tr>",
+        parse
+    ));
+    for part in &day.parts {
+        card.push_str(&render_part(part));
+    }
+    card.push_str("</table>");
+    if let Some(svg) = &day.visualization {
+        card.push_str(&format!("<div class=\"viz\">{}</div>", svg));
+    }
+    card.push_str("</div>");
+    card
+}
+
+/// Renders a self-contained HTML page with one card per day.
+pub fn render(days: &[DayReport]) -> String {
+    let stars = days
+        .iter()
+        .flat_map(|d| &d.parts)
+        .filter(|p| p.verdict == Verdict::Correct)
+        .count();
+    let cards = days.iter().map(render_day).collect::<Vec<_>>().join("\n");
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Advent of Code 2023</title>\
+<style>{}</style></head>\n<body><h1>Advent of Code 2023 &mdash; {} verified stars</h1>\
+<div class=\"days\">\n{}\n</div></body></html>\n",
+        STYLE, stars, cards
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_card() {
+        let day = DayReport {
+            day: 5,
+            parse: Some(Duration::from_millis(2)),
+            parts: vec![
+                PartReport {
+                    part: 1,
+                    outcome: Outcome::Solved {
+                        answer: 35,
+                        elapsed: Duration::from_millis(1),
+                    },
+                    verdict: Verdict::Correct,
+                },
+                PartReport {
+                    part: 2,
+                    outcome: Outcome::Panic("<oops>".to_string()),
+                    verdict: Verdict::Wrong { expected: 46 },
+                },
+            ],
+            visualization: Some("<svg></svg>".to_string()),
+            error: None,
+        };
+        let html = render(&[day]);
+        assert!(html.contains("<h2>Day 5</h2>"));
+        assert!(html.contains("1 verified stars"));
+        assert!(html.contains("panicked: &lt;oops&gt;"));
+        assert!(html.contains("expected 46"));
+        assert!(html.contains("<div class=\"viz\"><svg></svg></div>"));
+    }
+
+    #[test]
+    fn test_catch_panics() {
+        assert_eq!(catch("parser", || 3), Ok(3));
+        let error = catch("visualiser", || -> String { panic!("bad map") }).unwrap_err();
+        assert_eq!(error, "visualiser panicked: bad map");
+
+        let day = DayReport {
+            day: 5,
+            parse: None,
+            parts: vec![PartReport {
+                part: 1,
+                outcome: Outcome::Cancelled,
+                verdict: Verdict::Unknown,
+            }],
+            visualization: None,
+            error: Some(error),
+        };
+        let html = render(&[day]);
+        assert!(html.contains("<p class=\"wrong\">visualiser panicked: bad map</p><table>"));
+        assert!(html.contains("<td>Part 1</td>"));
+    }
+}
@@ -0,0 +1,478 @@
+use crate::error::SolveError;
+use crate::progress::{CancelFlag, Cancelled, Event, Progress};
+use crate::registry::{self, Solution, Streaming};
+use core::fmt;
+use std::cell::Cell;
+use std::fmt::Formatter;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Once;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Hidden CLI subcommand the runner uses to re-invoke itself for one day/part.
+pub const CHILD_COMMAND: &str = "__solve";
+
+/// How a single day/part run ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved { answer: usize, elapsed: Duration },
+    Timeout(Duration),
+    OutOfMemory,
+    Panic(String),
+    Cancelled,
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn answer(&self) -> Option<usize> {
+        match self {
+            Outcome::Solved { answer, .. } => Some(*answer),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Solved { answer, elapsed } => write!(f, "{} ({:.2?})", answer, elapsed),
+            Outcome::Timeout(limit) => write!(f, "timed out after {:?}", limit),
+            Outcome::OutOfMemory => write!(f, "out of memory"),
+            Outcome::Panic(message) => write!(f, "panicked: {}", message),
+            Outcome::Cancelled => write!(f, "cancelled"),
+            Outcome::Failed(reason) => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+/// Resource limits applied to every isolated run.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub timeout: Duration,
+    /// Address-space limit in bytes, only enforced on unix.
+    pub memory: Option<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            timeout: Duration::from_secs(60),
+            memory: Some(4 << 30),
+        }
+    }
+}
+
+/// Runs a solution in the current process, turning panics into an `Outcome`.
+pub fn run(solution: &Solution, input: &str, progress: &mut dyn Progress) -> Outcome {
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| (solution.solve)(input, progress)));
+    let elapsed = start.elapsed();
+    match result {
+        Ok(Ok(answer)) => Outcome::Solved { answer, elapsed },
+        Ok(Err(SolveError::Cancelled(_))) => Outcome::Cancelled,
+        Ok(Err(e)) => Outcome::Failed(e.to_string()),
+        Err(payload) => Outcome::Panic(panic_message(payload.as_ref())),
+    }
+}
+
+/// Like `run`, but the solver reads its input from `reader` as it goes.
+pub fn run_stream(streaming: &Streaming, reader: &mut dyn BufRead) -> Outcome {
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| (streaming.solve)(reader)));
+    let elapsed = start.elapsed();
+    match result {
+        Ok(Ok(answer)) => Outcome::Solved { answer, elapsed },
+        Ok(Err(e)) => Outcome::Failed(e.to_string()),
+        Err(payload) => Outcome::Panic(panic_message(payload.as_ref())),
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+thread_local! {
+    // Set while `catch_quietly` runs on this thread.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f`, returning the message of any panic instead of letting the panic
+/// hook print it, so callers that report the panic themselves do not also get
+/// it on stderr. Panics on other threads are printed as usual.
+pub fn catch_quietly<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                hook(info);
+            }
+        }));
+    });
+    let quiet = QUIET.with(|quiet| quiet.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.with(|q| q.set(quiet));
+    result.map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Runs a solution in a child process of `exe` under the given limits.
+///
+/// Progress events printed by the child are forwarded to `progress`, and the
+/// child is killed once the timeout expires. Once `progress` is cancelled, the
+/// child is told on its stdin, so its solver stops at its next step.
+pub fn run_isolated(
+    exe: &Path,
+    solution: &Solution,
+    input: &Path,
+    limits: &Limits,
+    progress: &mut (dyn Progress + Send),
+) -> Outcome {
+    let mut command = Command::new(exe);
+    command
+        .arg(CHILD_COMMAND)
+        .arg(solution.day.to_string())
+        .arg(solution.part.to_string())
+        .arg(input)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(bytes) = limits.memory {
+        limit_address_space(&mut command, bytes);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return Outcome::Failed(e.to_string()),
+    };
+
+    let mut stderr = child.stderr.take().unwrap();
+    let stderr_reader = thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        buf
+    });
+
+    let mut stdin = child.stdin.take();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let start = Instant::now();
+    let (status, answer) = thread::scope(|scope| {
+        let stdout_reader = scope.spawn(|| {
+            let mut answer = None;
+            for line in stdout.lines().map_while(Result::ok) {
+                match parse_line(&line) {
+                    Some(Message::Event(event)) => {
+                        progress.event(event);
+                        if progress.is_cancelled() {
+                            if let Some(mut stdin) = stdin.take() {
+                                let _ = writeln!(stdin, "{}", CANCEL_LINE);
+                            }
+                        }
+                    }
+                    Some(Message::Answer(a, elapsed)) => answer = Some(Ok((a, elapsed))),
+                    Some(Message::Error(e)) => answer = Some(Err(e)),
+                    None => (),
+                }
+            }
+            answer
+        });
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) if start.elapsed() >= limits.timeout => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break Err(Outcome::Timeout(limits.timeout));
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(10)),
+                Err(e) => break Err(Outcome::Failed(e.to_string())),
+            }
+        };
+        (status, stdout_reader.join().unwrap())
+    });
+    let stderr = stderr_reader.join().unwrap();
+
+    match status {
+        Ok(status) => classify(status, answer, &stderr),
+        Err(outcome) => outcome,
+    }
+}
+
+#[cfg(unix)]
+fn limit_address_space(command: &mut Command, bytes: u64) {
+    use std::os::unix::process::CommandExt;
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+    // SAFETY: setrlimit is async-signal-safe and only touches the forked child.
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_address_space(_command: &mut Command, _bytes: u64) {}
+
+// `answer` is what the child reported on stdout: its answer, or why the solver
+// gave up.
+fn classify(
+    status: ExitStatus,
+    answer: Option<Result<(usize, Duration), SolveError>>,
+    stderr: &str,
+) -> Outcome {
+    match (status.success(), answer) {
+        (true, Some(Ok((answer, elapsed)))) => return Outcome::Solved { answer, elapsed },
+        (_, Some(Err(SolveError::Cancelled(_)))) => return Outcome::Cancelled,
+        (_, Some(Err(e))) => return Outcome::Failed(e.to_string()),
+        _ => (),
+    }
+    // The default allocation error handler prints this line and then aborts.
+    if stderr.contains("memory allocation of") {
+        return Outcome::OutOfMemory;
+    }
+    if let Some(message) = child_panic_message(stderr) {
+        return Outcome::Panic(message);
+    }
+    Outcome::Failed(format!("child exited with {}", status))
+}
+
+// Pulls the message out of "thread 'main' panicked at src/day7.rs:1:2:\n<message>".
+fn child_panic_message(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+    lines.find(|line| line.contains("panicked at"))?;
+    let message = lines
+        .take_while(|line| !line.starts_with("note:"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(message)
+}
+
+enum Message {
+    Event(Event),
+    Answer(usize, Duration),
+    Error(SolveError),
+}
+
+/// What the parent writes to the child's stdin to cancel its solver.
+const CANCEL_LINE: &str = "cancel";
+
+// Text goes on one protocol line, so line breaks in it become spaces.
+fn one_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+fn format_event(event: &Event) -> String {
+    match event {
+        Event::Stage(stage) => format!("stage {}", one_line(stage)),
+        Event::Step { done, total } => format!("step {} {}", done, total),
+        Event::Value { name, value } => format!("value {} {}", one_line(name), value),
+        Event::Note(note) => format!("note {}", one_line(note)),
+    }
+}
+
+fn parse_line(line: &str) -> Option<Message> {
+    if line == "cancelled" {
+        return Some(Message::Error(SolveError::Cancelled(Cancelled)));
+    }
+    let (kind, rest) = line.split_once(' ')?;
+    match kind {
+        "stage" => Some(Message::Event(Event::Stage(rest.to_string()))),
+        "note" => Some(Message::Event(Event::Note(rest.to_string()))),
+        "step" => {
+            let (done, total) = rest.split_once(' ')?;
+            Some(Message::Event(Event::Step {
+                done: done.parse().ok()?,
+                total: total.parse().ok()?,
+            }))
+        }
+        "value" => {
+            let (name, value) = rest.rsplit_once(' ')?;
+            Some(Message::Event(Event::Value {
+                name: name.to_string(),
+                value: value.parse().ok()?,
+            }))
+        }
+        "invalid" => Some(Message::Error(SolveError::Invalid(rest.to_string()))),
+        "answer" => {
+            let (answer, nanos) = rest.split_once(' ')?;
+            Some(Message::Answer(
+                answer.parse().ok()?,
+                Duration::from_nanos(nanos.parse().ok()?),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Writes progress events to stdout using the runner's line protocol, and is
+/// cancelled once the parent asks on stdin.
+struct StdoutProgress(CancelFlag);
+
+impl Progress for StdoutProgress {
+    fn event(&mut self, event: Event) {
+        println!("{}", format_event(&event));
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_set()
+    }
+}
+
+/// Entry point of the child process: `__solve <day> <part> <input path>`.
+pub fn child_main(args: &[String]) -> ! {
+    let (day, part, input) = match args {
+        [day, part, input] => (day, part, input),
+        _ => {
+            eprintln!("usage: {} <day> <part> <input>", CHILD_COMMAND);
+            std::process::exit(2);
+        }
+    };
+    let solution = match (day.parse(), part.parse()) {
+        (Ok(day), Ok(part)) => registry::find(day, part),
+        _ => None,
+    };
+    let Some(solution) = solution else {
+        eprintln!("no solution registered for day {} part {}", day, part);
+        std::process::exit(2);
+    };
+    let input = match std::fs::read_to_string(input) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("could not read {}: {}", input, e);
+            std::process::exit(2);
+        }
+    };
+    let cancel = CancelFlag::new();
+    let flag = cancel.clone();
+    thread::spawn(move || {
+        let mut lines = std::io::stdin().lines().map_while(Result::ok);
+        if lines.any(|line| line == CANCEL_LINE) {
+            flag.cancel();
+        }
+    });
+    let start = Instant::now();
+    let answer = (solution.solve)(&input, &mut StdoutProgress(cancel));
+    let elapsed = start.elapsed();
+    match answer {
+        Ok(answer) => {
+            println!("answer {} {}", answer, elapsed.as_nanos());
+            let _ = std::io::stdout().flush();
+            std::process::exit(0);
+        }
+        Err(e) => {
+            match e {
+                SolveError::Cancelled(_) => println!("cancelled"),
+                SolveError::Invalid(reason) => println!("invalid {}", one_line(&reason)),
+            }
+            let _ = std::io::stdout().flush();
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+
+    #[test]
+    fn test_run_catches_panic() {
+        let solution = Solution {
+            day: 0,
+            part: 1,
+            variant: registry::DEFAULT_VARIANT,
+            solve: |_, _| panic!("Invalid hand with joker"),
+        };
+        let outcome = run(&solution, "", &mut NoProgress);
+        assert_eq!(
+            outcome,
+            Outcome::Panic("Invalid hand with joker".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_stream() {
+        let streaming = registry::streaming(7, 2).unwrap();
+        let mut reader = "32T3K 765\nT55J5 684\n".as_bytes();
+        assert_eq!(run_stream(streaming, &mut reader).answer(), Some(2133));
+        let mut reader = "32T3K 765\nnot a hand\n".as_bytes();
+        assert!(matches!(
+            run_stream(streaming, &mut reader),
+            Outcome::Panic(_)
+        ));
+    }
+
+    #[test]
+    fn test_catch_quietly() {
+        assert_eq!(catch_quietly(|| 7), Ok(7));
+        assert_eq!(
+            catch_quietly(|| -> usize { panic!("bad input") }),
+            Err("bad input".to_string())
+        );
+    }
+
+    #[test]
+    fn test_child_panic_message() {
+        let stderr = "\nthread 'main' (42) panicked at src/day7.rs:88:17:\nInvalid hand with joker\nnote: run with `RUST_BACKTRACE=1`\n";
+        assert_eq!(
+            child_panic_message(stderr),
+            Some("Invalid hand with joker".to_string())
+        );
+    }
+
+    #[test]
+    fn test_protocol_round_trip() {
+        let event = Event::Value {
+            name: "min".to_string(),
+            value: 35,
+        };
+        match parse_line(&format_event(&event)) {
+            Some(Message::Event(parsed)) => assert_eq!(parsed, event),
+            _ => panic!("event did not round trip"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_events_stay_on_one_line() {
+        let stage = Event::Stage("mapping\nseeds".to_string());
+        let Some(Message::Event(Event::Stage(parsed))) = parse_line(&format_event(&stage)) else {
+            panic!("stage line not recognised");
+        };
+        assert_eq!(parsed, "mapping seeds");
+    }
+
+    #[test]
+    fn test_child_cancelled() {
+        use std::os::unix::process::ExitStatusExt;
+        let Some(Message::Error(cancelled)) = parse_line("cancelled") else {
+            panic!("cancelled line not recognised");
+        };
+        let exited = ExitStatus::from_raw(1 << 8);
+        assert_eq!(
+            classify(exited, Some(Err(cancelled)), ""),
+            Outcome::Cancelled
+        );
+        let Some(Message::Error(invalid)) = parse_line("invalid no maps") else {
+            panic!("invalid line not recognised");
+        };
+        assert_eq!(
+            classify(exited, Some(Err(invalid)), ""),
+            Outcome::Failed("invalid input: no maps".to_string())
+        );
+        assert!(matches!(classify(exited, None, ""), Outcome::Failed(_)));
+    }
+}
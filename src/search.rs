@@ -0,0 +1,274 @@
+//! Breadth-first search, Dijkstra and A* over any neighbour function.
+//!
+//! Nodes only need to be hashable, so the same searches run on grid cells, on
+//! [`Graph`] nodes or on puzzle states built for one day.
+
+use crate::day3;
+use crate::grid::{self, Cell, Connectivity};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Everything a search learned about the nodes it reached.
+#[derive(Debug, Clone)]
+pub struct SearchResult<N> {
+    pub distances: HashMap<N, usize>,
+    /// The node each reached node was first reached from. The start has none.
+    pub predecessors: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> SearchResult<N> {
+    fn new(start: N) -> SearchResult<N> {
+        SearchResult {
+            distances: HashMap::from([(start, 0)]),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    /// The nodes from the start to `to`, both included, or `None` if the search
+    /// never reached `to`.
+    pub fn path(&self, to: &N) -> Option<Vec<N>> {
+        self.distances.get(to)?;
+        let mut path = vec![to.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// A route to a goal and what it costs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: usize,
+    pub nodes: Vec<N>,
+}
+
+/// Shortest paths by number of steps from `start` to every reachable node.
+pub fn bfs<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> SearchResult<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut result = SearchResult::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = result.distances[&node];
+        for next in neighbours(&node) {
+            if result.distances.contains_key(&next) {
+                continue;
+            }
+            result.distances.insert(next.clone(), distance + 1);
+            result.predecessors.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+    result
+}
+
+/// Cheapest paths from `start` to every reachable node, where `neighbours`
+/// yields each neighbour with the cost of stepping to it.
+pub fn dijkstra<N, I>(start: N, neighbours: impl FnMut(&N) -> I) -> SearchResult<N>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let (result, _) = best_first(start, |_| false, neighbours, |_| 0);
+    result
+}
+
+/// The cheapest path from `start` to a node for which `is_goal` holds.
+///
+/// `heuristic` must never overestimate the remaining cost, or the path found
+/// may not be the cheapest. A heuristic of zero makes this Dijkstra.
+pub fn astar<N, I>(
+    start: N,
+    is_goal: impl Fn(&N) -> bool,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl Fn(&N) -> usize,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let (result, goal) = best_first(start, is_goal, neighbours, heuristic);
+    let goal = goal?;
+    Some(Path {
+        cost: result.distances[&goal],
+        nodes: result.path(&goal).unwrap(),
+    })
+}
+
+// Shared by Dijkstra and A*: expands nodes cheapest estimate first, and stops
+// early at the first goal taken off the queue.
+fn best_first<N, I>(
+    start: N,
+    is_goal: impl Fn(&N) -> bool,
+    mut neighbours: impl FnMut(&N) -> I,
+    heuristic: impl Fn(&N) -> usize,
+) -> (SearchResult<N>, Option<N>)
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut result = SearchResult::new(start.clone());
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, start))]);
+    while let Some(Reverse((_, distance, node))) = queue.pop() {
+        // A node can be queued again once a cheaper way to it turns up.
+        if distance > result.distances[&node] {
+            continue;
+        }
+        if is_goal(&node) {
+            return (result, Some(node));
+        }
+        for (next, cost) in neighbours(&node) {
+            let next_distance = distance + cost;
+            if result
+                .distance(&next)
+                .is_some_and(|known| known <= next_distance)
+            {
+                continue;
+            }
+            result.distances.insert(next.clone(), next_distance);
+            result.predecessors.insert(next.clone(), node.clone());
+            let estimate = next_distance + heuristic(&next);
+            queue.push(Reverse((estimate, next_distance, next)));
+        }
+    }
+    (result, None)
+}
+
+/// Steps between two cells when moving only along rows and columns.
+pub fn manhattan(a: Cell, b: Cell) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// A neighbour function for `day3::Grid` cells that only steps onto cells whose
+/// character `passable` accepts.
+pub fn grid_neighbours<'a>(
+    grid: &'a day3::Grid,
+    connectivity: Connectivity,
+    passable: impl Fn(char) -> bool + 'a,
+) -> impl Fn(&Cell) -> Vec<Cell> + 'a {
+    let size = grid.size();
+    move |&cell| {
+        grid::neighbours(size, cell, connectivity)
+            .filter(|&(i, j)| passable(grid.get(i, j)))
+            .collect()
+    }
+}
+
+/// Like [`grid_neighbours`], with each step costing one, for Dijkstra and A*.
+pub fn weighted_grid_neighbours<'a>(
+    grid: &'a day3::Grid,
+    connectivity: Connectivity,
+    passable: impl Fn(char) -> bool + 'a,
+) -> impl Fn(&Cell) -> Vec<(Cell, usize)> + 'a {
+    let neighbours = grid_neighbours(grid, connectivity, passable);
+    move |cell| neighbours(cell).into_iter().map(|c| (c, 1)).collect()
+}
+
+/// A directed graph stored as adjacency lists with weighted edges.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    edges: HashMap<N, Vec<(N, usize)>>,
+}
+
+impl<N: Clone + Eq + Hash> Default for Graph<N> {
+    fn default() -> Self {
+        Graph {
+            edges: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Graph<N> {
+        Graph::default()
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, cost: usize) {
+        self.edges.entry(to.clone()).or_default();
+        self.edges.entry(from).or_default().push((to, cost));
+    }
+
+    /// Adds an edge each way with the same cost.
+    pub fn add_undirected_edge(&mut self, a: N, b: N, cost: usize) {
+        self.add_edge(a.clone(), b.clone(), cost);
+        self.add_edge(b, a, cost);
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    /// Neighbours of `node` with the cost of each edge, for Dijkstra and A*.
+    pub fn neighbours(&self, node: &N) -> Vec<(N, usize)> {
+        self.edges.get(node).cloned().unwrap_or_default()
+    }
+
+    /// Neighbours of `node` ignoring costs, for BFS.
+    pub fn successors(&self, node: &N) -> Vec<N> {
+        self.edges
+            .get(node)
+            .map(|edges| edges.iter().map(|(n, _)| n.clone()).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "#######\n#.....#\n#.###.#\n#.#...#\n#...#.#\n#######\n";
+
+    #[test]
+    fn test_bfs_on_grid() {
+        let grid = day3::parse_input(MAZE);
+        let open = grid_neighbours(&grid, Connectivity::Four, |c| c == '.');
+        let result = bfs((1, 1), open);
+        assert_eq!(result.distance(&(4, 5)), Some(7));
+        assert_eq!(result.distance(&(0, 0)), None);
+        let path = result.path(&(3, 3)).unwrap();
+        assert_eq!(
+            path,
+            vec![(1, 1), (2, 1), (3, 1), (4, 1), (4, 2), (4, 3), (3, 3)]
+        );
+        assert_eq!(result.path(&(0, 0)), None);
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let grid = day3::parse_input(MAZE);
+        let open = weighted_grid_neighbours(&grid, Connectivity::Four, |c| c == '.');
+        let goal = (4, 5);
+        let all = dijkstra((1, 1), &open);
+        let path = astar((1, 1), |&c| c == goal, &open, |&c| manhattan(c, goal)).unwrap();
+        assert_eq!(Some(path.cost), all.distance(&goal));
+        assert_eq!(path.nodes.len(), path.cost + 1);
+        assert_eq!(astar((1, 1), |&c| c == (0, 0), &open, |_| 0), None);
+    }
+
+    #[test]
+    fn test_graph() {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b", 7);
+        graph.add_edge("a", "c", 2);
+        graph.add_edge("c", "b", 3);
+        graph.add_undirected_edge("b", "d", 1);
+        assert_eq!(graph.nodes().count(), 4);
+
+        let result = dijkstra("a", |n| graph.neighbours(n));
+        assert_eq!(result.distance(&"d"), Some(6));
+        assert_eq!(result.path(&"d"), Some(vec!["a", "c", "b", "d"]));
+
+        let result = bfs("a", |n| graph.successors(n));
+        assert_eq!(result.distance(&"d"), Some(2));
+        assert_eq!(bfs("d", |n| graph.successors(n)).distance(&"a"), None);
+    }
+}
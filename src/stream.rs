@@ -0,0 +1,49 @@
+//! Line-by-line input for days whose puzzles are one record per line, so huge
+//! inputs can be solved without loading them into memory.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Path that stands for standard input on the command line.
+pub const STDIN: &str = "-";
+
+/// Opens `path` for buffered reading, or standard input for `-`.
+pub fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if path == Path::new(STDIN) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    Ok(Box::new(BufReader::new(File::open(path)?)))
+}
+
+/// Calls `f` on every line of `reader` without its line ending, reusing one
+/// buffer, so memory stays bounded by the longest line.
+pub fn for_each_line(mut reader: impl BufRead, mut f: impl FnMut(&str)) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        f(line.trim_end_matches(['\n', '\r']));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_each_line() {
+        let mut lines = Vec::new();
+        for_each_line("a\r\nbc\n\nd".as_bytes(), |line| {
+            lines.push(line.to_string())
+        })
+        .unwrap();
+        assert_eq!(lines, vec!["a", "bc", "", "d"]);
+
+        let invalid: &[u8] = &[b'a', b'\n', 0xff, b'\n'];
+        let error = for_each_line(invalid, |_| ()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
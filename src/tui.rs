@@ -0,0 +1,416 @@
+use crate::answers::{self, Answers, Verdict};
+use crate::progress::{CancelFlag, Event, Progress};
+use crate::registry::{self, InputKind, Solution};
+use crate::runner::{self, Outcome};
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DAYS_IN_CALENDAR: u8 = 25;
+
+enum Message {
+    Event(Event),
+    Done(Outcome),
+}
+
+/// Forwards solver events to the UI thread and relays cancellation back.
+struct ChannelProgress {
+    sender: Sender<Message>,
+    cancel: CancelFlag,
+}
+
+impl Progress for ChannelProgress {
+    fn event(&mut self, event: Event) {
+        let _ = self.sender.send(Message::Event(event));
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_set()
+    }
+}
+
+struct Running {
+    day: u8,
+    part: u8,
+    input: InputKind,
+    started: Instant,
+    cancel: CancelFlag,
+    receiver: Receiver<Message>,
+    step: Option<(usize, usize)>,
+    values: Vec<(String, usize)>,
+}
+
+/// State of the dashboard, kept apart from drawing so it can be tested.
+pub struct App {
+    answers: Answers,
+    example_answers: Answers,
+    selected: u8,
+    input: InputKind,
+    results: HashMap<(u8, u8, InputKind), Outcome>,
+    log: Vec<String>,
+    scroll: u16,
+    running: Option<Running>,
+    quit: bool,
+}
+
+impl App {
+    pub fn new(answers: Answers, example_answers: Answers) -> Self {
+        App {
+            answers,
+            example_answers,
+            selected: 1,
+            input: InputKind::Real,
+            results: HashMap::new(),
+            log: Vec::new(),
+            scroll: 0,
+            running: None,
+            quit: false,
+        }
+    }
+
+    /// One character per part: `*` verified, `+` solved but unverified, `.` not solved.
+    pub fn stars(&self, day: u8) -> String {
+        (1..=2)
+            .map(|part| match registry::find(day, part) {
+                None => '.',
+                Some(_) if self.answers.get(day, part).is_some() => '*',
+                Some(_) => '+',
+            })
+            .collect()
+    }
+
+    fn answers_for(&self, input: InputKind) -> &Answers {
+        match input {
+            InputKind::Real => &self.answers,
+            InputKind::Example => &self.example_answers,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.cancel();
+                self.quit = true;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.max(2) - 1,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(DAYS_IN_CALENDAR)
+            }
+            KeyCode::Char('e') => self.input = self.input.toggle(),
+            KeyCode::Char('1') => self.start(1),
+            KeyCode::Char('2') => self.start(2),
+            KeyCode::Char('c') => self.cancel(),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            _ => (),
+        }
+    }
+
+    fn cancel(&mut self) {
+        if let Some(running) = &self.running {
+            running.cancel.cancel();
+        }
+    }
+
+    fn start(&mut self, part: u8) {
+        if self.running.is_some() {
+            self.log
+                .push("a solver is already running, press c to cancel it".to_string());
+            return;
+        }
+        let day = self.selected;
+        let Some(solution) = registry::find(day, part) else {
+            self.log
+                .push(format!("day {} part {} is not solved yet", day, part));
+            return;
+        };
+        let path = self.input.path(day);
+        let input = match std::fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                self.log.push(format!("{}: {}", path.display(), e));
+                return;
+            }
+        };
+        self.log.push(format!(
+            "running day {} part {} on {}",
+            day,
+            part,
+            path.display()
+        ));
+        let (sender, receiver) = mpsc::channel();
+        let cancel = CancelFlag::new();
+        spawn_solver(solution, input, sender, cancel.clone());
+        self.running = Some(Running {
+            day,
+            part,
+            input: self.input,
+            started: Instant::now(),
+            cancel,
+            receiver,
+            step: None,
+            values: Vec::new(),
+        });
+    }
+
+    /// Drains pending solver messages, finishing the run when its result arrives.
+    pub fn poll(&mut self) {
+        let Some(running) = &mut self.running else {
+            return;
+        };
+        let mut done = None;
+        while let Ok(message) = running.receiver.try_recv() {
+            match message {
+                Message::Event(Event::Stage(stage)) => self.log.push(format!("> {}", stage)),
+                Message::Event(Event::Note(note)) => self.log.push(note),
+                Message::Event(Event::Step { done, total }) => running.step = Some((done, total)),
+                Message::Event(Event::Value { name, value }) => {
+                    match running.values.iter_mut().find(|(n, _)| *n == name) {
+                        Some(entry) => entry.1 = value,
+                        None => running.values.push((name, value)),
+                    }
+                }
+                Message::Done(outcome) => done = Some(outcome),
+            }
+        }
+        if let Some(outcome) = done {
+            let running = self.running.take().unwrap();
+            self.log.push(format!(
+                "day {} part {}: {}",
+                running.day, running.part, outcome
+            ));
+            self.results
+                .insert((running.day, running.part, running.input), outcome);
+        }
+    }
+
+    fn result_line(&self, part: u8) -> Line<'static> {
+        let day = self.selected;
+        if let Some(running) = &self.running {
+            if (running.day, running.part, running.input) == (day, part, self.input) {
+                return Line::from(format!(
+                    "Part {}: running {:.1?}",
+                    part,
+                    running.started.elapsed()
+                ))
+                .yellow();
+            }
+        }
+        if registry::find(day, part).is_none() {
+            return Line::from(format!("Part {}: not solved", part)).dark_gray();
+        }
+        let Some(outcome) = self.results.get(&(day, part, self.input)) else {
+            return Line::from(format!("Part {}: press {} to run", part, part));
+        };
+        let verdict = self
+            .answers_for(self.input)
+            .check(day, part, outcome.answer());
+        let (mark, color) = match verdict {
+            Verdict::Correct => ("correct".to_string(), Color::Green),
+            Verdict::Wrong { expected } => (format!("expected {}", expected), Color::Red),
+            Verdict::Unknown => ("unverified".to_string(), Color::Gray),
+        };
+        Line::from(vec![
+            Span::raw(format!("Part {}: {} ", part, outcome)),
+            Span::styled(mark, Style::new().fg(color)),
+        ])
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [days, detail] =
+            Layout::horizontal([Constraint::Length(14), Constraint::Min(0)]).areas(main);
+        let [results, gauge, output] = Layout::vertical([
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+        .areas(detail);
+
+        self.draw_days(frame, days);
+
+        let input = match self.input {
+            InputKind::Real => "real",
+            InputKind::Example => "example",
+        };
+        let title = format!(" Day {} - {} input ", self.selected, input);
+        frame.render_widget(
+            Paragraph::new(vec![self.result_line(1), self.result_line(2)])
+                .block(Block::bordered().title(title)),
+            results,
+        );
+
+        self.draw_gauge(frame, gauge);
+
+        frame.render_widget(
+            Paragraph::new(
+                self.log
+                    .iter()
+                    .map(|l| Line::from(l.as_str()))
+                    .collect::<Vec<_>>(),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .block(Block::bordered().title(" Output ")),
+            output,
+        );
+
+        frame.render_widget(
+            Line::from(" up/down day  e input  1/2 run  c cancel  PgUp/PgDn scroll  q quit")
+                .dark_gray(),
+            help,
+        );
+    }
+
+    fn draw_days(&self, frame: &mut Frame, area: Rect) {
+        let items = (1..=DAYS_IN_CALENDAR)
+            .map(|day| {
+                let item = ListItem::new(format!("{:>2} {}", day, self.stars(day)));
+                if registry::day(day).is_some() {
+                    item
+                } else {
+                    item.dark_gray()
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut state = ListState::default().with_selected(Some(self.selected as usize - 1));
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title(" Days "))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            area,
+            &mut state,
+        );
+    }
+
+    fn draw_gauge(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(" Progress ");
+        let Some(running) = &self.running else {
+            frame.render_widget(Paragraph::new("idle").block(block), area);
+            return;
+        };
+        let ratio = match running.step {
+            Some((done, total)) if total > 0 => done as f64 / total as f64,
+            _ => 0.0,
+        };
+        let mut label = format!("{:.1?}", running.started.elapsed());
+        for (name, value) in &running.values {
+            label.push_str(&format!("  {}={}", name, value));
+        }
+        frame.render_widget(
+            Gauge::default()
+                .block(block)
+                .gauge_style(Style::new().fg(Color::Green))
+                .ratio(ratio.min(1.0))
+                .label(label),
+            area,
+        );
+    }
+}
+
+fn spawn_solver(
+    solution: &'static Solution,
+    input: String,
+    sender: Sender<Message>,
+    cancel: CancelFlag,
+) {
+    thread::spawn(move || {
+        let mut progress = ChannelProgress {
+            sender: sender.clone(),
+            cancel,
+        };
+        // The outcome carries any panic message to the log; printed by the
+        // default hook it would land on top of the dashboard.
+        let outcome = runner::catch_quietly(|| runner::run(solution, &input, &mut progress))
+            .unwrap_or_else(Outcome::Panic);
+        let _ = sender.send(Message::Done(outcome));
+    });
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> std::io::Result<()> {
+    while !app.quit {
+        app.poll();
+        terminal.draw(|frame| app.draw(frame))?;
+        // Redraw at least ten times a second so the timer keeps moving.
+        if event::poll(Duration::from_millis(100))? {
+            if let TermEvent::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            {
+                app.handle_key(code);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the dashboard until the user quits.
+pub fn run() -> std::io::Result<()> {
+    let answers = Answers::load(Path::new(answers::ANSWERS_PATH)).unwrap_or_default();
+    let example_answers =
+        Answers::load(Path::new(answers::EXAMPLE_ANSWERS_PATH)).unwrap_or_default();
+    let mut app = App::new(answers, example_answers);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stars() {
+        let answers = Answers::parse("5 1 51580674\n").unwrap();
+        let app = App::new(answers, Answers::default());
+        assert_eq!(app.stars(5), "*+");
+        assert_eq!(app.stars(7), ".+");
+        assert_eq!(app.stars(25), "..");
+    }
+
+    #[test]
+    fn test_run_example_to_completion() {
+        let mut app = App::new(Answers::default(), Answers::default());
+        app.handle_key(KeyCode::Char('e'));
+        for _ in 1..6 {
+            app.handle_key(KeyCode::Down);
+        }
+        assert_eq!(app.selected, 6);
+        app.handle_key(KeyCode::Char('1'));
+        while app.running.is_some() {
+            thread::sleep(Duration::from_millis(1));
+            app.poll();
+        }
+        let outcome = &app.results[&(6, 1, InputKind::Example)];
+        assert_eq!(outcome.answer(), Some(288));
+    }
+
+    #[test]
+    fn test_draw() {
+        let app = App::new(Answers::default(), Answers::default());
+        let backend = ratatui::backend::TestBackend::new(80, 20);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert!(screen.contains("Day 1 - real input"));
+        assert!(screen.contains("Part 1: press 1 to run"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Checks `day7::find_type` against a brute-force classifier on every possible
+//! hand, and counts how often each hand type comes up.
+
+use crate::day7::{find_type, Card, HandType};
+use crate::runner;
+use core::fmt;
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+
+/// The 13 card ranks under either rule set. With jokers, `J` is rank 1 and
+/// ranks below 2; without them it is an ordinary jack, rank 11.
+pub fn ranks(jokers: bool) -> Vec<u8> {
+    let jack = if jokers { 1 } else { 11 };
+    vec![jack, 2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 13, 14]
+}
+
+/// Classifies a hand without any jokers, by how often each rank appears.
+fn plain_type(cards: &[Card]) -> HandType {
+    let mut counts = cards
+        .iter()
+        .map(|c| cards.iter().filter(|d| d.rank == c.rank).count())
+        .collect::<Vec<_>>();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    match (counts[0], counts[3]) {
+        (5, _) => HandType::FiveOfAKind,
+        (4, _) => HandType::FourOfAKind,
+        (3, 2) => HandType::FullHouse,
+        (3, _) => HandType::ThreeOfAKind,
+        (2, 2) => HandType::TwoPair,
+        (2, _) => HandType::OnePair,
+        _ => HandType::HighCard,
+    }
+}
+
+/// The best type over every way of replacing each joker with any other rank.
+pub fn reference_type(cards: &[Card]) -> HandType {
+    let Some(joker) = cards.iter().position(|c| c.rank == 1) else {
+        return plain_type(cards);
+    };
+    ranks(true)
+        .into_iter()
+        .filter(|&rank| rank != 1)
+        .map(|rank| {
+            let mut substituted = cards.to_vec();
+            substituted[joker] = Card { rank };
+            reference_type(&substituted)
+        })
+        .max()
+        .unwrap()
+}
+
+/// A hand on which `find_type` and the reference disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub hand: String,
+    pub expected: HandType,
+    /// What `find_type` returned, or the message it panicked with.
+    pub actual: Result<HandType, String>,
+}
+
+/// The outcome of classifying all 13^5 hands under one rule set.
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub jokers: bool,
+    /// How many hands the reference puts in each type.
+    pub counts: BTreeMap<HandType, usize>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Verification {
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rules = if self.jokers {
+            "with jokers"
+        } else {
+            "without jokers"
+        };
+        writeln!(f, "Hand types {} ({} hands):", rules, self.total())?;
+        for (hand_type, count) in &self.counts {
+            let share = 100.0 * *count as f64 / self.total() as f64;
+            writeln!(
+                f,
+                "  {:<13} {:>7}  {:>6.2}%",
+                format!("{:?}", hand_type),
+                count,
+                share
+            )?;
+        }
+        match self.mismatches.len() {
+            0 => write!(f, "find_type agrees on every hand"),
+            n => write!(f, "find_type disagrees on {} hands", n),
+        }
+    }
+}
+
+/// Classifies every hand with both `find_type` and the reference.
+pub fn verify(jokers: bool) -> Verification {
+    let ranks = ranks(jokers);
+    let mut counts = BTreeMap::new();
+    let mut mismatches = Vec::new();
+    let mut indices = [0; 5];
+    loop {
+        let cards = indices.map(|i| Card { rank: ranks[i] });
+        let expected = reference_type(&cards);
+        *counts.entry(expected).or_insert(0) += 1;
+        let actual = runner::catch_quietly(|| find_type(&cards));
+        if actual != Ok(expected) {
+            mismatches.push(Mismatch {
+                hand: cards.iter().map(|c| c.to_char()).collect(),
+                expected,
+                actual,
+            });
+        }
+        // Count up in base 13, last card fastest.
+        let Some(i) = indices.iter().rposition(|&i| i < ranks.len() - 1) else {
+            break;
+        };
+        indices[i] += 1;
+        indices[i + 1..].fill(0);
+    }
+    Verification {
+        jokers,
+        counts,
+        mismatches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_type_without_jokers() {
+        let verification = verify(false);
+        assert_eq!(verification.mismatches, vec![]);
+        assert_eq!(verification.total(), 13usize.pow(5));
+        let counts = verification.counts.values().copied().collect::<Vec<_>>();
+        // HighCard up to FiveOfAKind, as counted by hand.
+        assert_eq!(counts, vec![154440, 171600, 25740, 17160, 1560, 780, 13]);
+    }
+
+    #[test]
+    fn test_find_type_with_jokers() {
+        let verification = verify(true);
+        assert_eq!(verification.mismatches, vec![]);
+        assert_eq!(verification.total(), 13usize.pow(5));
+        assert!(verification
+            .to_string()
+            .ends_with("find_type agrees on every hand"));
+    }
+}
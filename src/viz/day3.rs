@@ -0,0 +1,154 @@
+use crate::day3::{self, Grid};
+use crate::viz::{svg_document, svg_text, RESET};
+
+// Size of one grid cell in the SVG, in pixels.
+const CELL_WIDTH: usize = 8;
+const CELL_HEIGHT: usize = 14;
+
+/// What a schematic cell contributes to the answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    IncludedPart,
+    ExcludedPart,
+    Symbol,
+    Gear,
+}
+
+impl Cell {
+    fn ansi(self) -> &'static str {
+        match self {
+            Cell::Empty => "\x1b[2m",
+            Cell::IncludedPart => "\x1b[32m",
+            Cell::ExcludedPart => "\x1b[31m",
+            Cell::Symbol => "\x1b[33m",
+            Cell::Gear => "\x1b[1;35m",
+        }
+    }
+
+    fn svg_fill(self) -> &'static str {
+        match self {
+            Cell::Empty => "#333",
+            Cell::IncludedPart => "#00cc00",
+            Cell::ExcludedPart => "#ff4444",
+            Cell::Symbol => "#ffff66",
+            Cell::Gear => "#ff66ff",
+        }
+    }
+}
+
+/// Classifies every cell the same way `day3::solve` sees it.
+pub fn classify(grid: &Grid) -> Vec<Vec<Cell>> {
+    let mut cells = (0..grid.height())
+        .map(|i| {
+            (0..grid.width(i))
+                .map(|j| match grid.get(i, j) {
+                    c if day3::is_symbol(&c) => Cell::Symbol,
+                    _ => Cell::Empty,
+                })
+                .collect::<Vec<Cell>>()
+        })
+        .collect::<Vec<_>>();
+    let parts = day3::extract_parts(grid);
+    for part in &parts {
+        let kind = if day3::symbol_adjacent(grid, part) {
+            Cell::IncludedPart
+        } else {
+            Cell::ExcludedPart
+        };
+        for (i, j) in part.cells() {
+            cells[i][j] = kind;
+        }
+    }
+    for gear in day3::find_gears(grid, &parts) {
+        cells[gear.i][gear.j] = Cell::Gear;
+    }
+    cells
+}
+
+pub fn render_ansi(grid: &Grid) -> String {
+    let mut out = String::new();
+    for (i, row) in classify(grid).iter().enumerate() {
+        let mut current = None;
+        for (j, cell) in row.iter().enumerate() {
+            // Only switch colours at the boundary between two kinds of cell.
+            if current != Some(*cell) {
+                out.push_str(RESET);
+                out.push_str(cell.ansi());
+                current = Some(*cell);
+            }
+            out.push(grid.get(i, j));
+        }
+        out.push_str(RESET);
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "{}part{} {}excluded{} {}symbol{} {}gear{}\n",
+        Cell::IncludedPart.ansi(),
+        RESET,
+        Cell::ExcludedPart.ansi(),
+        RESET,
+        Cell::Symbol.ansi(),
+        RESET,
+        Cell::Gear.ansi(),
+        RESET
+    ));
+    out
+}
+
+pub fn render_svg(grid: &Grid) -> String {
+    let cells = classify(grid);
+    let width = (0..grid.height()).map(|i| grid.width(i)).max().unwrap_or(0);
+    let mut body = String::from("<g font-family=\"monospace\" font-size=\"12\">");
+    for (i, row) in cells.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            // Dots are left out, which keeps large inputs small.
+            if *cell == Cell::Empty {
+                continue;
+            }
+            let c = grid.get(i, j).to_string();
+            body.push_str(&svg_text(
+                j * CELL_WIDTH,
+                (i + 1) * CELL_HEIGHT - 3,
+                cell.svg_fill(),
+                &c,
+            ));
+        }
+    }
+    body.push_str("</g>");
+    svg_document(width * CELL_WIDTH, grid.height() * CELL_HEIGHT, &body)
+}
+
+pub fn visualize_terminal(input: &str) -> String {
+    render_ansi(&day3::parse_input(input))
+}
+
+pub fn visualize(input: &str) -> String {
+    render_svg(&day3::parse_input(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "467..114..\n...*......\n..35..633.\n......#...\n617*......\n......+.58.\n..592.....\n......755.\n...$.*....\n664.598..\n";
+
+    #[test]
+    fn test_classify() {
+        let cells = classify(&day3::parse_input(TEST_INPUT));
+        assert_eq!(cells[0][0], Cell::IncludedPart);
+        assert_eq!(cells[0][5], Cell::ExcludedPart);
+        assert_eq!(cells[1][3], Cell::Gear);
+        assert_eq!(cells[3][6], Cell::Symbol);
+        assert_eq!(cells[4][3], Cell::Symbol);
+        assert_eq!(cells[0][3], Cell::Empty);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = visualize("1*2\n");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("fill=\"#ff66ff\">*</text>"));
+        assert!(svg.contains("fill=\"#00cc00\">1</text>"));
+    }
+}
@@ -0,0 +1,288 @@
+use crate::day5::{self, Almanac, Map};
+use crate::viz::{svg_document, svg_text, RESET};
+
+// Number of terminal columns used for the number line of each stage.
+const COLUMNS: usize = 64;
+// Geometry of one stage panel in the SVG, in pixels.
+const PANEL_WIDTH: usize = 800;
+const PANEL_HEIGHT: usize = 120;
+const MARGIN: usize = 90;
+const PALETTE: [(&str, &str); 6] = [
+    ("\x1b[36m", "#33cccc"),
+    ("\x1b[35m", "#cc66ff"),
+    ("\x1b[34m", "#6688ff"),
+    ("\x1b[32m", "#33cc66"),
+    ("\x1b[33m", "#cccc33"),
+    ("\x1b[31m", "#ff6666"),
+];
+const TRACE_ANSI: &str = "\x1b[1;37m";
+const TRACE_FILL: &str = "#ffffff";
+
+/// One map together with the traced (source piece, destination piece) pairs.
+pub struct Stage<'a> {
+    pub map: &'a Map,
+    pub pieces: Vec<((usize, usize), (usize, usize))>,
+}
+
+impl Stage<'_> {
+    pub fn inputs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.pieces.iter().map(|(input, _)| *input)
+    }
+
+    pub fn outputs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.pieces.iter().map(|(_, output)| *output)
+    }
+
+    // Largest number drawn in this stage, used to scale the number line.
+    fn extent(&self) -> usize {
+        let ranges = self
+            .map
+            .ranges
+            .iter()
+            .flat_map(|r| [r.source_start + r.length, r.destination_start + r.length]);
+        let pieces = self.inputs().chain(self.outputs()).map(|(_, end)| end);
+        ranges.chain(pieces).max().unwrap_or(0).max(1)
+    }
+}
+
+/// Follows `interval` through every map, splitting it wherever ranges cut it.
+pub fn trace_stages<'a>(maps: &[&'a Map], interval: (usize, usize)) -> Vec<Stage<'a>> {
+    let mut current = vec![interval];
+    let mut stages = Vec::new();
+    for map in maps {
+        let pieces = current
+            .iter()
+            .flat_map(|&piece| map.split_interval(piece))
+            .collect::<Vec<_>>();
+        current = pieces.iter().map(|(_, output)| *output).collect();
+        stages.push(Stage { map, pieces });
+    }
+    stages
+}
+
+fn range_label(index: usize) -> char {
+    (b'a' + (index % 26) as u8) as char
+}
+
+fn format_interval((start, end): (usize, usize)) -> String {
+    format!("[{}, {})", start, end)
+}
+
+// Columns covered by [start, end) on a line of `COLUMNS` cells scaled to `extent`.
+fn columns((start, end): (usize, usize), extent: usize) -> std::ops::Range<usize> {
+    let scale = |x: usize| (x as u128 * COLUMNS as u128 / extent as u128) as usize;
+    let first = scale(start).min(COLUMNS - 1);
+    let last = scale(end).clamp(first + 1, COLUMNS);
+    first..last
+}
+
+fn number_line(spans: &[(std::ops::Range<usize>, char, &str)]) -> String {
+    let mut cells: Vec<(char, &str)> = vec![('·', "\x1b[2m"); COLUMNS];
+    // Earlier spans are drawn last so that, like `Map::apply`, the first range wins.
+    for (cols, c, color) in spans.iter().rev() {
+        for cell in &mut cells[cols.clone()] {
+            *cell = (*c, color);
+        }
+    }
+    let mut line = String::new();
+    let mut current = "";
+    for (c, color) in cells {
+        if color != current {
+            line.push_str(RESET);
+            line.push_str(color);
+            current = color;
+        }
+        line.push(c);
+    }
+    line.push_str(RESET);
+    line
+}
+
+pub fn render_ansi(stages: &[Stage]) -> String {
+    let mut out = String::new();
+    for stage in stages {
+        let extent = stage.extent();
+        let ranges = stage.map.ranges.iter().enumerate();
+        let sources = ranges
+            .clone()
+            .map(|(i, r)| {
+                let cols = columns((r.source_start, r.source_start + r.length), extent);
+                (cols, range_label(i), PALETTE[i % PALETTE.len()].0)
+            })
+            .collect::<Vec<_>>();
+        let destinations = ranges
+            .map(|(i, r)| {
+                let cols = columns(
+                    (r.destination_start, r.destination_start + r.length),
+                    extent,
+                );
+                (cols, range_label(i), PALETTE[i % PALETTE.len()].0)
+            })
+            .collect::<Vec<_>>();
+        let inputs = stage
+            .inputs()
+            .map(|p| (columns(p, extent), '█', TRACE_ANSI))
+            .collect::<Vec<_>>();
+        let outputs = stage
+            .outputs()
+            .map(|p| (columns(p, extent), '█', TRACE_ANSI))
+            .collect::<Vec<_>>();
+
+        out.push_str(&format!(
+            "{} → {}  (0 .. {})\n",
+            stage.map.source, stage.map.destination, extent
+        ));
+        out.push_str(&format!("  src │{}│\n", number_line(&sources)));
+        out.push_str(&format!("  dst │{}│\n", number_line(&destinations)));
+        out.push_str(&format!("  in  │{}│\n", number_line(&inputs)));
+        out.push_str(&format!("  out │{}│\n", number_line(&outputs)));
+        for (i, r) in stage.map.ranges.iter().enumerate() {
+            out.push_str(&format!(
+                "  {}{}{}: {} → {}\n",
+                PALETTE[i % PALETTE.len()].0,
+                range_label(i),
+                RESET,
+                format_interval((r.source_start, r.source_start + r.length)),
+                format_interval((r.destination_start, r.destination_start + r.length)),
+            ));
+        }
+        for (input, output) in &stage.pieces {
+            out.push_str(&format!(
+                "  {}{}{} → {}\n",
+                TRACE_ANSI,
+                format_interval(*input),
+                RESET,
+                format_interval(*output)
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// Trapezoid joining a span on the source axis to a span on the destination axis.
+fn band(
+    (source, destination): ((usize, usize), (usize, usize)),
+    extent: usize,
+    top: usize,
+    fill: &str,
+    opacity: f32,
+) -> String {
+    let plot = PANEL_WIDTH - MARGIN - 10;
+    let x = |v: usize| MARGIN as f64 + v as f64 * plot as f64 / extent as f64;
+    let (y1, y2) = (top + 30, top + 90);
+    format!(
+        "<polygon points=\"{:.1},{} {:.1},{} {:.1},{} {:.1},{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+        x(source.0),
+        y1,
+        x(source.1),
+        y1,
+        x(destination.1),
+        y2,
+        x(destination.0),
+        y2,
+        fill,
+        opacity
+    )
+}
+
+pub fn render_svg(stages: &[Stage]) -> String {
+    let mut body = String::from("<g font-family=\"monospace\" font-size=\"12\">");
+    for (n, stage) in stages.iter().enumerate() {
+        let top = n * PANEL_HEIGHT;
+        let extent = stage.extent();
+        body.push_str(&svg_text(4, top + 34, "#cccccc", &stage.map.source));
+        body.push_str(&svg_text(4, top + 94, "#cccccc", &stage.map.destination));
+        body.push_str(&svg_text(
+            MARGIN,
+            top + 16,
+            "#888888",
+            &format!("0 .. {}", extent),
+        ));
+        for y in [top + 30, top + 90] {
+            body.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#555\"/>",
+                MARGIN,
+                y,
+                PANEL_WIDTH - 10,
+                y
+            ));
+        }
+        for (i, r) in stage.map.ranges.iter().enumerate() {
+            let spans = (
+                (r.source_start, r.source_start + r.length),
+                (r.destination_start, r.destination_start + r.length),
+            );
+            body.push_str(&band(
+                spans,
+                extent,
+                top,
+                PALETTE[i % PALETTE.len()].1,
+                0.35,
+            ));
+        }
+        for &piece in &stage.pieces {
+            body.push_str(&band(piece, extent, top, TRACE_FILL, 0.8));
+        }
+    }
+    body.push_str("</g>");
+    svg_document(PANEL_WIDTH, stages.len() * PANEL_HEIGHT, &body)
+}
+
+// The first seed range of the almanac, which is what the pictures trace by
+// default. The almanac is `None` if its maps are invalid; the lint says why.
+fn first_interval(input: &str) -> (Option<Almanac>, (usize, usize)) {
+    let (seeds, maps) = day5::parse_input(input);
+    let interval = seeds.intervals().first().copied().unwrap_or((0, 1));
+    (Almanac::new(maps).ok(), interval)
+}
+
+// The seed-to-location chain, or no stages at all if the almanac has none.
+fn chain(almanac: &Option<Almanac>) -> Vec<&Map> {
+    almanac
+        .as_ref()
+        .and_then(|almanac| almanac.seed_to_location().ok())
+        .unwrap_or_default()
+}
+
+pub fn visualize_terminal(input: &str) -> String {
+    let (almanac, interval) = first_interval(input);
+    render_ansi(&trace_stages(&chain(&almanac), interval))
+}
+
+pub fn visualize(input: &str) -> String {
+    let (almanac, interval) = first_interval(input);
+    render_svg(&trace_stages(&chain(&almanac), interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_stages() {
+        let input = std::fs::read_to_string("data/test_input5.txt").unwrap();
+        let (_seeds, maps) = day5::parse_input(&input);
+        let almanac = Almanac::new(maps).unwrap();
+        let stages = trace_stages(&almanac.seed_to_location().unwrap(), (79, 93));
+        assert_eq!(stages.len(), 7);
+        assert_eq!(stages[0].map.destination, "soil");
+        assert_eq!(stages[0].outputs().collect::<Vec<_>>(), vec![(81, 95)]);
+        let min = stages[6].outputs().map(|(start, _)| start).min();
+        assert_eq!(min, Some(46));
+    }
+
+    #[test]
+    fn test_invalid_almanac_has_no_stages() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n0 0 1\n\nseed-to-soil map:\n1 0 1";
+        assert_eq!(visualize_terminal(input), render_ansi(&[]));
+    }
+
+    #[test]
+    fn test_columns() {
+        assert_eq!(columns((0, 50), 100), 0..32);
+        // Tiny spans still get one column.
+        assert_eq!(columns((10, 11), 1_000_000), 0..1);
+        assert_eq!(columns((99, 100), 100), 63..64);
+    }
+}
@@ -0,0 +1,27 @@
+pub mod day3;
+pub mod day5;
+
+use crate::report::escape;
+
+pub const RESET: &str = "\x1b[0m";
+
+/// Wraps `body` in an SVG root element with a dark background.
+pub fn svg_document(width: usize, height: usize, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+viewBox=\"0 0 {w} {h}\"><rect width=\"{w}\" height=\"{h}\" fill=\"#0f0f23\"/>{body}</svg>",
+        w = width,
+        h = height,
+        body = body
+    )
+}
+
+pub fn svg_text(x: usize, y: usize, fill: &str, text: &str) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>",
+        x,
+        y,
+        fill,
+        escape(text)
+    )
+}
@@ -0,0 +1,211 @@
+use crate::answers::{self, Answers, Verdict};
+use crate::progress::NoProgress;
+use crate::registry::{self, InputKind};
+use crate::runner::{self, Limits, Outcome};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification times of every watched file.
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// The day's module plus every file under `data/`.
+pub fn watched_paths(day: u8) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(format!("src/day{}.rs", day))];
+    if let Ok(entries) = std::fs::read_dir("data") {
+        paths.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()));
+    }
+    paths
+}
+
+pub fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+            Some((path.clone(), modified))
+        })
+        .collect()
+}
+
+/// Files that were added, removed or modified between two snapshots.
+pub fn changed_paths(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut changed = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+/// Describes how an answer moved compared to the previous run.
+pub fn answer_change(previous: Option<&Outcome>, current: &Outcome) -> String {
+    match (previous.and_then(Outcome::answer), current.answer()) {
+        (Some(before), Some(after)) if before != after => {
+            format!("changed from {}", before)
+        }
+        (Some(_), Some(_)) => "unchanged".to_string(),
+        (None, Some(_)) if previous.is_some() => "now solves".to_string(),
+        (Some(before), None) => format!("was {}", before),
+        _ => String::new(),
+    }
+}
+
+fn verdict_label(verdict: Verdict) -> String {
+    match verdict {
+        Verdict::Correct => "pass".to_string(),
+        Verdict::Wrong { expected } => format!("FAIL, expected {}", expected),
+        Verdict::Unknown => "no expected answer".to_string(),
+    }
+}
+
+// Rebuilds with the same profile as the running binary.
+fn rebuild(exe: &Path) -> bool {
+    let mut cargo = Command::new("cargo");
+    cargo.args(["build", "--quiet"]);
+    if exe.components().any(|c| c.as_os_str() == "release") {
+        cargo.arg("--release");
+    }
+    match cargo.status() {
+        Ok(status) => status.success(),
+        Err(e) => {
+            eprintln!("could not run cargo: {}", e);
+            false
+        }
+    }
+}
+
+fn run_day(
+    exe: &Path,
+    day: u8,
+    limits: &Limits,
+    previous: &BTreeMap<(InputKind, u8), Outcome>,
+) -> BTreeMap<(InputKind, u8), Outcome> {
+    // Answers are re-read each time since they live in the watched data/ directory.
+    let answers = Answers::load(Path::new(answers::ANSWERS_PATH)).unwrap_or_default();
+    let example_answers =
+        Answers::load(Path::new(answers::EXAMPLE_ANSWERS_PATH)).unwrap_or_default();
+    let mut results = BTreeMap::new();
+    for kind in [InputKind::Example, InputKind::Real] {
+        let path = kind.path(day);
+        if !path.exists() {
+            continue;
+        }
+        for solution in registry::for_day(day) {
+            let outcome = runner::run_isolated(exe, solution, &path, limits, &mut NoProgress);
+            let label = match kind {
+                InputKind::Example => {
+                    verdict_label(example_answers.check(day, solution.part, outcome.answer()))
+                }
+                InputKind::Real => {
+                    let change = answer_change(previous.get(&(kind, solution.part)), &outcome);
+                    match answers.check(day, solution.part, outcome.answer()) {
+                        Verdict::Unknown => change,
+                        verdict if change.is_empty() => verdict_label(verdict),
+                        verdict => format!("{}, {}", change, verdict_label(verdict)),
+                    }
+                }
+            };
+            let name = match kind {
+                InputKind::Example => "example",
+                InputKind::Real => "real",
+            };
+            println!(
+                "  {:<7} part {}: {}  [{}]",
+                name, solution.part, outcome, label
+            );
+            results.insert((kind, solution.part), outcome);
+        }
+    }
+    results
+}
+
+/// Rebuilds and re-runs `day` every time its module or an input changes.
+pub fn watch(day: u8, limits: &Limits) -> ! {
+    let exe = std::env::current_exe().unwrap();
+    let paths = watched_paths(day);
+    let mut last = snapshot(&paths);
+    println!("Watching src/day{}.rs and data/ (ctrl-c to stop)", day);
+    let mut results = run_day(&exe, day, limits, &BTreeMap::new());
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        // Re-list so newly added data files are picked up.
+        let current = snapshot(&watched_paths(day));
+        let changed = changed_paths(&last, &current);
+        if changed.is_empty() {
+            continue;
+        }
+        last = current;
+        let names = changed
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>();
+        println!("\n{} changed, rebuilding...", names.join(", "));
+        if !rebuild(&exe) {
+            println!("  build failed, waiting for the next change");
+            continue;
+        }
+        results = run_day(&exe, day, limits, &results);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_paths() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + Duration::from_secs(1);
+        let before = Snapshot::from([
+            (PathBuf::from("src/day5.rs"), t0),
+            (PathBuf::from("data/input5.txt"), t0),
+            (PathBuf::from("data/old.txt"), t0),
+        ]);
+        let after = Snapshot::from([
+            (PathBuf::from("src/day5.rs"), t1),
+            (PathBuf::from("data/input5.txt"), t0),
+            (PathBuf::from("data/new.txt"), t0),
+        ]);
+        assert_eq!(
+            changed_paths(&before, &after),
+            vec![
+                PathBuf::from("data/new.txt"),
+                PathBuf::from("src/day5.rs"),
+                PathBuf::from("data/old.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_answer_change() {
+        let solved = |answer| Outcome::Solved {
+            answer,
+            elapsed: Duration::ZERO,
+        };
+        assert_eq!(answer_change(None, &solved(35)), "");
+        assert_eq!(answer_change(Some(&solved(35)), &solved(35)), "unchanged");
+        assert_eq!(
+            answer_change(Some(&solved(35)), &solved(46)),
+            "changed from 35"
+        );
+        assert_eq!(
+            answer_change(Some(&Outcome::OutOfMemory), &solved(46)),
+            "now solves"
+        );
+        assert_eq!(
+            answer_change(Some(&solved(35)), &Outcome::OutOfMemory),
+            "was 35"
+        );
+    }
+}